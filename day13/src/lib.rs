@@ -1,3 +1,4 @@
+pub mod mirror;
 pub mod part1;
 pub mod part2;
 
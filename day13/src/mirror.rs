@@ -0,0 +1,181 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// Mirror line between two columns.
+    Vertical,
+    /// Mirror line between two rows.
+    Horizontal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflection {
+    pub axis: Axis,
+    /// Number of columns to the left (vertical) or rows above (horizontal) the line.
+    pub position: usize,
+    /// Cells `(row, column)` on the left/top side that differ from their mirror image.
+    pub smudges: Vec<(usize, usize)>,
+}
+
+impl Reflection {
+    /// Summary value used by the puzzle: columns to the left, or 100 times rows above.
+    pub fn summary(&self) -> usize {
+        match self.axis {
+            Axis::Vertical => self.position,
+            Axis::Horizontal => self.position * 100,
+        }
+    }
+
+    /// The cell mirrored across this reflection line.
+    pub fn mirror(&self, (row, column): (usize, usize)) -> (usize, usize) {
+        match self.axis {
+            Axis::Vertical => (row, self.position * 2 - column - 1),
+            Axis::Horizontal => (self.position * 2 - row - 1, column),
+        }
+    }
+}
+
+pub fn parse_pattern(pattern: &str) -> Vec<&[u8]> {
+    pattern.lines().map(str::as_bytes).collect()
+}
+
+/// Find every reflection line with exactly `smudges` differing cell pairs.
+pub fn find_reflections(map: &[&[u8]], smudges: usize) -> Vec<Reflection> {
+    let mut result = find_reflections_on_axis(map, Axis::Vertical, smudges);
+    result.extend(find_reflections_on_axis(map, Axis::Horizontal, smudges));
+    result
+}
+
+/// Find reflection lines on a single axis with exactly `smudges` differing cell pairs.
+pub fn find_reflections_on_axis(map: &[&[u8]], axis: Axis, smudges: usize) -> Vec<Reflection> {
+    let limit = match axis {
+        Axis::Vertical => map.first().map_or(0, |line| line.len()),
+        Axis::Horizontal => map.len(),
+    };
+    (1..limit)
+        .filter_map(|pos| {
+            let cells = match axis {
+                Axis::Vertical => vertical_differences(map, pos, smudges),
+                Axis::Horizontal => horizontal_differences(map, pos, smudges),
+            }?;
+            Some(Reflection {
+                axis,
+                position: pos,
+                smudges: cells,
+            })
+        })
+        .collect()
+}
+
+/// Range of indices on the near side of a line at `pos` which have a mirror image.
+fn mirrored_range(len: usize, pos: usize) -> std::ops::Range<usize> {
+    pos.saturating_sub(len.saturating_sub(pos))..pos.min(len)
+}
+
+/// Differing cells for a vertical line, or `None` if the count is not exactly `limit`.
+fn vertical_differences(map: &[&[u8]], pos: usize, limit: usize) -> Option<Vec<(usize, usize)>> {
+    let mut cells = Vec::new();
+    for (row, line) in map.iter().enumerate() {
+        for column in mirrored_range(line.len(), pos) {
+            if line[column] != line[pos * 2 - column - 1] {
+                if cells.len() == limit {
+                    return None;
+                }
+                cells.push((row, column));
+            }
+        }
+    }
+    (cells.len() == limit).then_some(cells)
+}
+
+/// Differing cells for a horizontal line, or `None` if the count is not exactly `limit`.
+fn horizontal_differences(map: &[&[u8]], pos: usize, limit: usize) -> Option<Vec<(usize, usize)>> {
+    let mut cells = Vec::new();
+    for row in mirrored_range(map.len(), pos) {
+        let mirror = map[pos * 2 - row - 1];
+        for (column, (a, b)) in map[row].iter().zip(mirror.iter()).enumerate() {
+            if a != b {
+                if cells.len() == limit {
+                    return None;
+                }
+                cells.push((row, column));
+            }
+        }
+    }
+    (cells.len() == limit).then_some(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example_without_smudges() {
+        let patterns = EXAMPLE.split("\n\n").map(parse_pattern).collect::<Vec<_>>();
+        assert_eq!(
+            find_reflections(&patterns[0], 0),
+            vec![Reflection {
+                axis: Axis::Vertical,
+                position: 5,
+                smudges: vec![],
+            }]
+        );
+        assert_eq!(
+            find_reflections(&patterns[1], 0),
+            vec![Reflection {
+                axis: Axis::Horizontal,
+                position: 4,
+                smudges: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn example_with_one_smudge() {
+        let patterns = EXAMPLE.split("\n\n").map(parse_pattern).collect::<Vec<_>>();
+        let first = find_reflections(&patterns[0], 1);
+        assert_eq!(
+            first,
+            vec![Reflection {
+                axis: Axis::Horizontal,
+                position: 3,
+                smudges: vec![(0, 0)],
+            }]
+        );
+        assert_eq!(first[0].mirror((0, 0)), (5, 0));
+        let second = find_reflections(&patterns[1], 1);
+        assert_eq!(
+            second,
+            vec![Reflection {
+                axis: Axis::Horizontal,
+                position: 1,
+                smudges: vec![(0, 4)],
+            }]
+        );
+        assert_eq!(second.iter().map(Reflection::summary).sum::<usize>(), 100);
+    }
+
+    #[test]
+    fn ragged_lines() {
+        // Later lines shorter than the first only mirror the columns they have.
+        let map = parse_pattern("#..#\n#.\n#");
+        assert_eq!(
+            find_reflections_on_axis(&map, Axis::Vertical, 0),
+            vec![Reflection {
+                axis: Axis::Vertical,
+                position: 2,
+                smudges: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn single_axis() {
+        let patterns = EXAMPLE.split("\n\n").map(parse_pattern).collect::<Vec<_>>();
+        assert!(find_reflections_on_axis(&patterns[0], Axis::Horizontal, 0).is_empty());
+        assert_eq!(
+            find_reflections_on_axis(&patterns[0], Axis::Vertical, 0)[0].position,
+            5
+        );
+    }
+}
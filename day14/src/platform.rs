@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Largest width and height supported by the `u128` row and column
+/// bitboards.
+pub const MAX_SIZE: usize = u128::BITS as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+impl TryFrom<u8> for Direction {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'N' => Ok(Direction::North),
+            b'W' => Ok(Direction::West),
+            b'S' => Ok(Direction::South),
+            b'E' => Ok(Direction::East),
+            _ => Err(format!("Invalid direction: {}", value as char)),
+        }
+    }
+}
+
+/// The order of tilts in one spin cycle.
+pub const SPIN_CYCLE: [Direction; 4] = [
+    Direction::North,
+    Direction::West,
+    Direction::South,
+    Direction::East,
+];
+
+/// A sequence of tilts which is applied as one cycle, such as `"NWSE"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TiltProgram(Vec<Direction>);
+
+impl TiltProgram {
+    pub fn new(directions: Vec<Direction>) -> Self {
+        Self(directions)
+    }
+
+    /// The puzzle's spin cycle: north, west, south, east.
+    pub fn spin_cycle() -> Self {
+        Self(SPIN_CYCLE.to_vec())
+    }
+
+    pub fn directions(&self) -> &[Direction] {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for TiltProgram {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value
+            .trim()
+            .bytes()
+            .map(Direction::try_from)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// Platform stored as one bitboard per row, with bit `n` set for column `n`.
+/// Vertical tilts work on column bitboards, with bit `n` set for row `n`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Platform {
+    width: usize,
+    rocks: Vec<u128>,
+    cubes: Vec<u128>,
+    /// Runs of columns between cube rocks for each row, as `(start, end)`.
+    row_segments: Vec<Vec<(u32, u32)>>,
+    /// Runs of rows between cube rocks for each column, as `(start, end)`.
+    column_segments: Vec<Vec<(u32, u32)>>,
+}
+
+impl Platform {
+    /// Fails if the platform is wider or taller than [`MAX_SIZE`].
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let width = input.lines().next().map_or(0, str::len);
+        let height = input.lines().count();
+        if width > MAX_SIZE || height > MAX_SIZE {
+            return Err(format!(
+                "Platform is {}x{}, larger than {}x{}",
+                width, height, MAX_SIZE, MAX_SIZE
+            ));
+        }
+
+        let mut rocks = vec![];
+        let mut cubes = vec![];
+        for line in input.lines() {
+            debug_assert_eq!(line.len(), width);
+            let (mut rock_row, mut cube_row) = (0, 0);
+            for (col, ch) in line.bytes().enumerate() {
+                match ch {
+                    b'O' => rock_row |= 1 << col,
+                    b'#' => cube_row |= 1 << col,
+                    _ => (),
+                }
+            }
+            rocks.push(rock_row);
+            cubes.push(cube_row);
+        }
+
+        let row_segments = cubes.iter().map(|row| segments(*row, width)).collect();
+        let column_segments = transpose(&cubes, width)
+            .iter()
+            .map(|column| segments(*column, height))
+            .collect();
+
+        Ok(Self {
+            width,
+            rocks,
+            cubes,
+            row_segments,
+            column_segments,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rocks.len()
+    }
+
+    /// Bitboards of the rounded rocks, one per row from north to south.
+    pub fn rocks(&self) -> &[u128] {
+        &self.rocks
+    }
+
+    /// Bitboards of the cube-shaped rocks, one per row from north to south.
+    pub fn cubes(&self) -> &[u128] {
+        &self.cubes
+    }
+
+    pub fn is_rock(&self, row: usize, col: usize) -> bool {
+        self.rocks[row] & (1 << col) != 0
+    }
+
+    pub fn is_cube(&self, row: usize, col: usize) -> bool {
+        self.cubes[row] & (1 << col) != 0
+    }
+
+    pub fn tilt(&mut self, direction: Direction) {
+        match direction {
+            Direction::North => self.tilt_north(),
+            Direction::South => self.tilt_south(),
+            Direction::West => self.tilt_west(),
+            Direction::East => self.tilt_east(),
+        }
+    }
+
+    /// Tilt north, then west, then south, then east.
+    pub fn spin_cycle(&mut self) {
+        for direction in SPIN_CYCLE {
+            self.tilt(direction);
+        }
+    }
+
+    /// Apply each tilt of the program in order.
+    pub fn run(&mut self, program: &TiltProgram) {
+        for direction in program.directions() {
+            self.tilt(*direction);
+        }
+    }
+
+    /// Total load on the north support beams.
+    pub fn north_load(&self) -> usize {
+        self.load(Direction::North)
+    }
+
+    /// Total load on the support beams along the given edge. Each rock adds
+    /// its distance in rows or columns from the opposite edge.
+    pub fn load(&self, edge: Direction) -> usize {
+        let height = self.height();
+        match edge {
+            Direction::North => self
+                .rocks
+                .iter()
+                .enumerate()
+                .map(|(row, bits)| bits.count_ones() as usize * (height - row))
+                .sum(),
+            Direction::South => self
+                .rocks
+                .iter()
+                .enumerate()
+                .map(|(row, bits)| bits.count_ones() as usize * (row + 1))
+                .sum(),
+            Direction::West => (0..self.width)
+                .map(|col| self.column_count(col) * (self.width - col))
+                .sum(),
+            Direction::East => (0..self.width)
+                .map(|col| self.column_count(col) * (col + 1))
+                .sum(),
+        }
+    }
+
+    fn column_count(&self, col: usize) -> usize {
+        self.rocks
+            .iter()
+            .filter(|bits| *bits & (1 << col) != 0)
+            .count()
+    }
+
+    fn tilt_north(&mut self) {
+        let mut columns = transpose(&self.rocks, self.width);
+        slide_to_start(&mut columns, &self.column_segments);
+        self.rocks = transpose(&columns, self.height());
+    }
+
+    fn tilt_south(&mut self) {
+        let mut columns = transpose(&self.rocks, self.width);
+        slide_to_end(&mut columns, &self.column_segments);
+        self.rocks = transpose(&columns, self.height());
+    }
+
+    fn tilt_west(&mut self) {
+        slide_to_start(&mut self.rocks, &self.row_segments);
+    }
+
+    fn tilt_east(&mut self) {
+        slide_to_end(&mut self.rocks, &self.row_segments);
+    }
+}
+
+/// Moves every rock in each board to the low end of its segment.
+fn slide_to_start(boards: &mut [u128], segments: &[Vec<(u32, u32)>]) {
+    for (board, segments) in boards.iter_mut().zip(segments) {
+        *board = segments.iter().fold(0, |acc, (start, end)| {
+            let count = (*board & span(*start, *end)).count_ones();
+            acc | (low_bits(count) << start)
+        });
+    }
+}
+
+/// Moves every rock in each board to the high end of its segment.
+fn slide_to_end(boards: &mut [u128], segments: &[Vec<(u32, u32)>]) {
+    for (board, segments) in boards.iter_mut().zip(segments) {
+        *board = segments.iter().fold(0, |acc, (start, end)| {
+            let count = (*board & span(*start, *end)).count_ones();
+            acc | low_bits(count).checked_shl(end - count).unwrap_or(0)
+        });
+    }
+}
+
+/// Swaps rows and columns of `len` bits, visiting only the set bits.
+fn transpose(boards: &[u128], len: usize) -> Vec<u128> {
+    let mut transposed = vec![0; len];
+    for (n, board) in boards.iter().enumerate() {
+        let mut bits = *board;
+        while bits != 0 {
+            transposed[bits.trailing_zeros() as usize] |= 1 << n;
+            bits &= bits - 1;
+        }
+    }
+    transposed
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.height() {
+            for col in 0..self.width {
+                let ch = if self.is_rock(row, col) {
+                    'O'
+                } else if self.is_cube(row, col) {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{ch}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bitmask with the lowest `count` bits set.
+fn low_bits(count: u32) -> u128 {
+    if count >= u128::BITS {
+        u128::MAX
+    } else {
+        (1 << count) - 1
+    }
+}
+
+/// Bitmask with bits `start..end` set.
+fn span(start: u32, end: u32) -> u128 {
+    low_bits(end - start) << start
+}
+
+/// Runs of bits between set bits of `cubes`, within the first `len` bits.
+fn segments(cubes: u128, len: usize) -> Vec<(u32, u32)> {
+    let mut segments = vec![];
+    let mut start = 0;
+    for bit in 0..len as u32 {
+        if cubes & (1 << bit) != 0 {
+            if start < bit {
+                segments.push((start, bit));
+            }
+            start = bit + 1;
+        }
+    }
+    if start < len as u32 {
+        segments.push((start, len as u32));
+    }
+    segments
+}
+
+/// Repeated runs of a tilt program on a platform, until the arrangement repeats.
+#[derive(Debug, Clone)]
+pub struct Cycles {
+    platform: Platform,
+    /// Rock bitboards after 0, 1, 2, ... cycles, up to the first repeat.
+    history: Vec<Vec<u128>>,
+    offset: usize,
+    period: usize,
+}
+
+impl Cycles {
+    pub fn new(mut platform: Platform, program: &TiltProgram) -> Self {
+        let mut seen = HashMap::new();
+        let mut history = vec![];
+        loop {
+            if let Some(&offset) = seen.get(&platform.rocks) {
+                let period = history.len() - offset;
+                return Self {
+                    platform,
+                    history,
+                    offset,
+                    period,
+                };
+            }
+            seen.insert(platform.rocks.clone(), history.len());
+            history.push(platform.rocks.clone());
+            platform.run(program);
+        }
+    }
+
+    /// Repeated spin cycles of the platform.
+    pub fn spin(platform: Platform) -> Self {
+        Self::new(platform, &TiltProgram::spin_cycle())
+    }
+
+    /// Number of cycles before the arrangement starts repeating.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Number of cycles between repeated arrangements.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// The platform after the given number of cycles.
+    pub fn after(&self, cycles: usize) -> Platform {
+        let index = if cycles < self.offset {
+            cycles
+        } else {
+            self.offset + (cycles - self.offset) % self.period
+        };
+        Platform {
+            rocks: self.history[index].clone(),
+            ..self.platform.clone()
+        }
+    }
+
+    /// Load on the support beams along the given edge after the given number of cycles.
+    pub fn load_after(&self, cycles: usize, edge: Direction) -> usize {
+        self.after(cycles).load(edge)
+    }
+
+    /// Load on the north support beams after the given number of cycles.
+    pub fn north_load_after(&self, cycles: usize) -> usize {
+        self.load_after(cycles, Direction::North)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    const AFTER_ONE_CYCLE: &str = "\
+.....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+";
+
+    const AFTER_THREE_CYCLES: &str = "\
+.....#....
+....#...O#
+.....##...
+..O#......
+.....OOO#.
+.O#...O#.#
+....O#...O
+.......OOO
+#...O###.O
+#.OOO#...O
+";
+
+    #[test]
+    fn parse_and_display() {
+        let platform = Platform::parse(EXAMPLE).unwrap();
+        assert_eq!(platform.to_string(), EXAMPLE);
+        assert_eq!((platform.width(), platform.height()), (10, 10));
+    }
+
+    #[test]
+    fn tilt_north() {
+        let mut platform = Platform::parse(EXAMPLE).unwrap();
+        platform.tilt(Direction::North);
+        assert_eq!(platform.north_load(), 136);
+    }
+
+    #[test]
+    fn spin_cycles() {
+        let mut platform = Platform::parse(EXAMPLE).unwrap();
+        platform.spin_cycle();
+        assert_eq!(platform.to_string(), AFTER_ONE_CYCLE);
+
+        let cycles = Cycles::spin(Platform::parse(EXAMPLE).unwrap());
+        assert_eq!(cycles.after(1).to_string(), AFTER_ONE_CYCLE);
+        assert_eq!(cycles.after(3).to_string(), AFTER_THREE_CYCLES);
+        assert_eq!((cycles.offset(), cycles.period()), (3, 7));
+        assert_eq!(cycles.north_load_after(1000000000), 64);
+    }
+
+    #[test]
+    fn tilt_program() {
+        let program = TiltProgram::try_from("NWSE").unwrap();
+        assert_eq!(program, TiltProgram::spin_cycle());
+        assert!(TiltProgram::try_from("NWX").is_err());
+
+        let cycles = Cycles::new(Platform::parse(EXAMPLE).unwrap(), &program);
+        assert_eq!(cycles.north_load_after(1000000000), 64);
+
+        let mut platform = Platform::parse(EXAMPLE).unwrap();
+        platform.run(&TiltProgram::try_from("NNEWS").unwrap());
+        let cycles = Cycles::new(
+            Platform::parse(EXAMPLE).unwrap(),
+            &TiltProgram::try_from("NNEWS").unwrap(),
+        );
+        assert_eq!(cycles.after(1), platform);
+        assert_eq!(cycles.offset() + cycles.period(), cycles.history.len());
+    }
+
+    #[test]
+    fn size_limit() {
+        let wide = ".".repeat(MAX_SIZE + 1);
+        assert_eq!(
+            Platform::parse(&wide),
+            Err("Platform is 129x1, larger than 128x128".to_string())
+        );
+        let tall = "O\n".repeat(MAX_SIZE + 1);
+        assert!(Platform::parse(&tall).is_err());
+
+        // The largest platform tilts in every direction.
+        let mut platform = Platform::parse(&format!("{}\n", "O.".repeat(64)).repeat(128)).unwrap();
+        platform.tilt(Direction::East);
+        platform.tilt(Direction::South);
+        assert_eq!(
+            platform.load(Direction::South),
+            64 * (1..=128).sum::<usize>()
+        );
+        assert!(platform.is_rock(0, 127) && !platform.is_rock(127, 0));
+    }
+
+    #[test]
+    fn load_on_each_edge() {
+        let platform = Platform::parse("O..\n...\n..O\n").unwrap();
+        assert_eq!(platform.load(Direction::North), 4);
+        assert_eq!(platform.load(Direction::South), 4);
+        assert_eq!(platform.load(Direction::West), 4);
+        assert_eq!(platform.load(Direction::East), 4);
+
+        let platform = Platform::parse("OO.\n...\n...\n").unwrap();
+        assert_eq!(platform.load(Direction::North), 6);
+        assert_eq!(platform.load(Direction::South), 2);
+        assert_eq!(platform.load(Direction::West), 5);
+        assert_eq!(platform.load(Direction::East), 3);
+    }
+}
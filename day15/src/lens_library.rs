@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+/// The Holiday ASCII String Helper algorithm.
+pub fn hash(substring: &str) -> usize {
+    substring
+        .as_bytes()
+        .iter()
+        .fold(0, |acc, c| ((acc + *c as usize) * 17) % 256)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lens<'a> {
+    pub label: &'a str,
+    pub focal_length: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step<'a> {
+    Remove(&'a str),
+    Insert(&'a str, u8),
+}
+
+impl<'a> Step<'a> {
+    pub fn try_parse(step: &'a str) -> Option<Self> {
+        if let Some(label) = step.strip_suffix('-') {
+            Some(Step::Remove(label))
+        } else {
+            let (label, focal_length) = step.split_once('=')?;
+            Some(Step::Insert(label, focal_length.parse().ok()?))
+        }
+    }
+}
+
+/// Parse a comma separated initialization sequence.
+pub fn parse_steps(input: &str) -> impl Iterator<Item = Step<'_>> {
+    input
+        .trim_end()
+        .split(',')
+        .map(|s| Step::try_parse(s).unwrap())
+}
+
+/// Lenses in a box, in insertion order. Removed lenses leave an empty slot
+/// which is reclaimed when the box is compacted.
+#[derive(Debug, Clone, Default)]
+struct LensBox<'a> {
+    slots: Vec<Option<Lens<'a>>>,
+    len: usize,
+}
+
+/// The HASHMAP: boxes of lenses selected by hashing each lens label.
+#[derive(Debug, Clone)]
+pub struct LensLibrary<'a> {
+    boxes: Vec<LensBox<'a>>,
+    /// Box number and slot of every lens in the library.
+    index: HashMap<&'a str, (usize, usize)>,
+    hasher: fn(&str) -> usize,
+}
+
+impl Default for LensLibrary<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> LensLibrary<'a> {
+    /// The puzzle's library of 256 boxes using [`hash`].
+    pub fn new() -> Self {
+        Self::with_hasher(256, hash)
+    }
+
+    /// A library with `box_count` boxes. The box for a label is the result of
+    /// `hasher` modulo the box count.
+    pub fn with_hasher(box_count: usize, hasher: fn(&str) -> usize) -> Self {
+        assert!(box_count > 0, "library needs at least one box");
+        Self {
+            boxes: vec![LensBox::default(); box_count],
+            index: HashMap::new(),
+            hasher,
+        }
+    }
+
+    pub fn box_count(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// Total number of lenses in all boxes.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn box_for(&self, label: &str) -> usize {
+        (self.hasher)(label) % self.boxes.len()
+    }
+
+    /// Insert a lens, or replace the focal length of the lens with the same
+    /// label in place. Returns the previous focal length.
+    pub fn insert(&mut self, label: &'a str, focal_length: u8) -> Option<u8> {
+        if let Some(&(box_no, slot)) = self.index.get(label) {
+            let lens = self.boxes[box_no].slots[slot].as_mut().unwrap();
+            return Some(std::mem::replace(&mut lens.focal_length, focal_length));
+        }
+        let box_no = self.box_for(label);
+        let lens_box = &mut self.boxes[box_no];
+        self.index.insert(label, (box_no, lens_box.slots.len()));
+        lens_box.slots.push(Some(Lens {
+            label,
+            focal_length,
+        }));
+        lens_box.len += 1;
+        None
+    }
+
+    /// Remove a lens, moving the lenses behind it forward. Returns its focal length.
+    pub fn remove(&mut self, label: &str) -> Option<u8> {
+        let (box_no, slot) = self.index.remove(label)?;
+        let lens_box = &mut self.boxes[box_no];
+        let lens = lens_box.slots[slot].take().unwrap();
+        lens_box.len -= 1;
+        if lens_box.slots.len() > 2 * lens_box.len + 8 {
+            self.compact(box_no);
+        }
+        Some(lens.focal_length)
+    }
+
+    pub fn get(&self, label: &str) -> Option<u8> {
+        let &(box_no, slot) = self.index.get(label)?;
+        self.boxes[box_no].slots[slot].map(|lens| lens.focal_length)
+    }
+
+    pub fn contains(&self, label: &str) -> bool {
+        self.index.contains_key(label)
+    }
+
+    pub fn apply(&mut self, step: Step<'a>) {
+        match step {
+            Step::Remove(label) => {
+                self.remove(label);
+            }
+            Step::Insert(label, focal_length) => {
+                self.insert(label, focal_length);
+            }
+        }
+    }
+
+    /// Lenses in the given box, from front to back.
+    pub fn lenses(&self, box_no: usize) -> impl Iterator<Item = &Lens<'a>> {
+        self.boxes[box_no].slots.iter().flatten()
+    }
+
+    /// Non-empty boxes in order, with their box number and lenses.
+    pub fn boxes(&self) -> impl Iterator<Item = (usize, impl Iterator<Item = &Lens<'a>>)> {
+        self.boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, lens_box)| lens_box.len > 0)
+            .map(|(box_no, lens_box)| (box_no, lens_box.slots.iter().flatten()))
+    }
+
+    /// Sum of box number times slot number times focal length, all one-based.
+    pub fn focusing_power(&self) -> usize {
+        self.boxes()
+            .map(|(box_no, lenses)| {
+                (box_no + 1)
+                    * lenses
+                        .enumerate()
+                        .map(|(n, lens)| (n + 1) * lens.focal_length as usize)
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+
+    fn compact(&mut self, box_no: usize) {
+        let lens_box = &mut self.boxes[box_no];
+        lens_box.slots.retain(Option::is_some);
+        for (slot, lens) in lens_box.slots.iter().flatten().enumerate() {
+            self.index.insert(lens.label, (box_no, slot));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let mut library = LensLibrary::new();
+        parse_steps(EXAMPLE).for_each(|step| library.apply(step));
+        assert_eq!(library.focusing_power(), 145);
+        assert_eq!(library.len(), 5);
+        assert_eq!(
+            library.lenses(3).map(|lens| lens.label).collect::<Vec<_>>(),
+            vec!["ot", "ab", "pc"]
+        );
+        assert_eq!(
+            library
+                .boxes()
+                .map(|(box_no, _)| box_no)
+                .collect::<Vec<_>>(),
+            vec![0, 3]
+        );
+    }
+
+    #[test]
+    fn insert_remove_get() {
+        let mut library = LensLibrary::with_hasher(1, hash);
+        assert_eq!(library.insert("a", 1), None);
+        assert_eq!(library.insert("b", 2), None);
+        assert_eq!(library.insert("a", 3), Some(1));
+        assert_eq!(library.get("a"), Some(3));
+        assert_eq!(library.remove("a"), Some(3));
+        assert_eq!(library.remove("a"), None);
+        assert_eq!(library.get("a"), None);
+        assert_eq!(library.insert("a", 4), None);
+        assert_eq!(
+            library.lenses(0).map(|lens| lens.label).collect::<Vec<_>>(),
+            vec!["b", "a"]
+        );
+    }
+
+    #[test]
+    fn compaction_keeps_order() {
+        let labels = (0..100).map(|n| n.to_string()).collect::<Vec<_>>();
+        let mut library = LensLibrary::with_hasher(4, |label| label.len());
+        for (n, label) in labels.iter().enumerate() {
+            library.insert(label, (n % 9 + 1) as u8);
+        }
+        for label in labels.iter().step_by(3) {
+            library.remove(label);
+        }
+        for label in labels.iter().step_by(3) {
+            assert!(!library.contains(label));
+        }
+        let expected = labels
+            .iter()
+            .enumerate()
+            .filter(|(n, label)| n % 3 != 0 && label.len() == 2)
+            .map(|(_, label)| label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            library.lenses(2).map(|lens| lens.label).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(library.get("98"), Some(9));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod lens_library;
 pub mod part1;
 pub mod part2;
 
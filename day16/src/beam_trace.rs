@@ -0,0 +1,258 @@
+use crate::contraption::{Beam, Contraption, Direction, Tile, TileSet};
+use arrayvec::ArrayVec;
+use std::fmt::Write;
+
+/// Size of one tile in rendered SVG images.
+const TILE_SIZE: usize = 20;
+
+/// Every direction a beam travels through each tile of a contraption.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeamTrace {
+    rows: usize,
+    cols: usize,
+    /// Bitmask of directions for each tile in row-major order.
+    directions: Vec<u8>,
+}
+
+impl BeamTrace {
+    pub fn new(contraption: &Contraption, start: Beam) -> Self {
+        let (rows, cols) = (contraption.rows(), contraption.cols());
+        let mut directions = vec![0; rows * cols];
+        let mut beams = vec![start];
+        while let Some(beam) = beams.pop() {
+            let (row, col) = beam.position;
+            let bit = direction_bit(beam.direction);
+            if directions[row * cols + col] & bit == 0 {
+                directions[row * cols + col] |= bit;
+                beams.extend(contraption.step(beam));
+            }
+        }
+        Self {
+            rows,
+            cols,
+            directions,
+        }
+    }
+
+    pub fn is_energized(&self, (row, col): (usize, usize)) -> bool {
+        self.directions[row * self.cols + col] != 0
+    }
+
+    /// Directions of the beams entering the tile.
+    pub fn directions(&self, (row, col): (usize, usize)) -> ArrayVec<Direction, 4> {
+        let bits = self.directions[row * self.cols + col];
+        Direction::ALL
+            .into_iter()
+            .filter(|direction| bits & direction_bit(*direction) != 0)
+            .collect()
+    }
+
+    pub fn energized(&self) -> TileSet {
+        let mut tiles = TileSet::new(self.rows, self.cols);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                if self.is_energized((row, col)) {
+                    tiles.insert((row, col));
+                }
+            }
+        }
+        tiles
+    }
+
+    pub fn energized_count(&self) -> usize {
+        self.directions.iter().filter(|bits| **bits != 0).count()
+    }
+
+    /// Energized tiles as `#` and the rest as `.`.
+    pub fn render_energized(&self) -> String {
+        let mut output = String::with_capacity((self.cols + 1) * self.rows);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                output.push(if self.is_energized((row, col)) {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// The contraption with beams drawn on its empty tiles, as in the puzzle
+    /// text: an arrow for a single beam, or the number of beams.
+    pub fn render_ascii(&self, contraption: &Contraption) -> String {
+        let mut output = String::with_capacity((self.cols + 1) * self.rows);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let tile = contraption.tile((row, col));
+                let directions = self.directions((row, col));
+                output.push(match (tile, directions.as_slice()) {
+                    (Tile::Empty, [direction]) => arrow(*direction),
+                    (Tile::Empty, [_, _, ..]) => {
+                        char::from_digit(directions.len() as u32, 10).unwrap()
+                    }
+                    _ => tile.into(),
+                });
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// The contraption as an SVG image, with energized tiles shaded and an
+    /// arrow for each beam direction through every tile.
+    pub fn render_svg(&self, contraption: &Contraption) -> String {
+        let (width, height) = (self.cols * TILE_SIZE, self.rows * TILE_SIZE);
+        let mut output = String::new();
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )
+        .unwrap();
+        writeln!(
+            output,
+            r##"<rect width="{width}" height="{height}" fill="#0f0f23"/>"##
+        )
+        .unwrap();
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let (x, y) = (col * TILE_SIZE, row * TILE_SIZE);
+                if self.is_energized((row, col)) {
+                    writeln!(
+                        output,
+                        r##"<rect x="{x}" y="{y}" width="{TILE_SIZE}" height="{TILE_SIZE}" fill="#ffff66" fill-opacity="0.3"/>"##
+                    )
+                    .unwrap();
+                }
+                if let Some((x1, y1, x2, y2)) = tile_line(contraption.tile((row, col))) {
+                    writeln!(
+                        output,
+                        r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#cccccc" stroke-width="2"/>"##,
+                        x + x1,
+                        y + y1,
+                        x + x2,
+                        y + y2
+                    )
+                    .unwrap();
+                }
+                for direction in self.directions((row, col)) {
+                    writeln!(
+                        output,
+                        r##"<polygon points="{}" fill="#ffff66"/>"##,
+                        arrow_points(x, y, direction)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        output.push_str("</svg>\n");
+        output
+    }
+}
+
+fn direction_bit(direction: Direction) -> u8 {
+    match direction {
+        Direction::Right => 1,
+        Direction::Left => 2,
+        Direction::Up => 4,
+        Direction::Down => 8,
+    }
+}
+
+fn arrow(direction: Direction) -> char {
+    match direction {
+        Direction::Right => '>',
+        Direction::Left => '<',
+        Direction::Up => '^',
+        Direction::Down => 'v',
+    }
+}
+
+/// Line drawn for a mirror or splitter, relative to the tile's top left corner.
+fn tile_line(tile: Tile) -> Option<(usize, usize, usize, usize)> {
+    let (near, far, mid) = (2, TILE_SIZE - 2, TILE_SIZE / 2);
+    match tile {
+        Tile::Empty => None,
+        Tile::NEMirror => Some((near, far, far, near)),
+        Tile::NWMirror => Some((near, near, far, far)),
+        Tile::HSplitter => Some((near, mid, far, mid)),
+        Tile::VSplitter => Some((mid, near, mid, far)),
+    }
+}
+
+/// Small triangle pointing in the beam direction, offset from the tile centre
+/// so that beams in different directions do not overlap.
+fn arrow_points(x: usize, y: usize, direction: Direction) -> String {
+    let (cx, cy) = (x + TILE_SIZE / 2, y + TILE_SIZE / 2);
+    let points = match direction {
+        Direction::Right => [(cx + 2, cy - 7), (cx + 8, cy - 4), (cx + 2, cy - 1)],
+        Direction::Left => [(cx - 2, cy + 1), (cx - 8, cy + 4), (cx - 2, cy + 7)],
+        Direction::Up => [(cx - 7, cy - 2), (cx - 4, cy - 8), (cx - 1, cy - 2)],
+        Direction::Down => [(cx + 1, cy + 2), (cx + 4, cy + 8), (cx + 7, cy + 2)],
+    };
+    points
+        .iter()
+        .map(|(px, py)| format!("{px},{py}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    const EXAMPLE_BEAMS: &str = "\
+>|<<<\\....
+|v-.\\^....
+.v...|->>>
+.v...v^.|.
+.v...v^...
+.v...v^..\\
+.v../2\\\\..
+<->-/vv|..
+.|<<<2-|.\\
+.v//.|.v..
+";
+
+    const EXAMPLE_ENERGIZED: &str = "\
+######....
+.#...#....
+.#...#####
+.#...##...
+.#...##...
+.#...##...
+.#..####..
+########..
+.#######..
+.#...#.#..
+";
+
+    #[test]
+    fn example_beams() {
+        let contraption = Contraption::parse(EXAMPLE);
+        let trace = BeamTrace::new(&contraption, Beam::new((0, 0), Direction::Right));
+        assert_eq!(trace.render_ascii(&contraption), EXAMPLE_BEAMS);
+        assert_eq!(trace.render_energized(), EXAMPLE_ENERGIZED);
+        assert_eq!(trace.energized_count(), 46);
+        assert_eq!(trace.energized().len(), 46);
+        assert_eq!(
+            trace.directions((6, 5)).as_slice(),
+            &[Direction::Right, Direction::Down]
+        );
+    }
+
+    #[test]
+    fn svg() {
+        let contraption = Contraption::parse(EXAMPLE);
+        let trace = BeamTrace::new(&contraption, Beam::new((0, 0), Direction::Right));
+        let svg = trace.render_svg(&contraption);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect x=").count(), 46);
+    }
+}
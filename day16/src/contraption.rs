@@ -0,0 +1,398 @@
+use arrayvec::ArrayVec;
+use grid::Grid;
+use itertools::chain;
+use pathfinding::directed::strongly_connected_components::strongly_connected_components;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Empty,
+    NEMirror,
+    NWMirror,
+    HSplitter,
+    VSplitter,
+}
+
+impl TryFrom<u8> for Tile {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'.' => Ok(Tile::Empty),
+            b'/' => Ok(Tile::NEMirror),
+            b'\\' => Ok(Tile::NWMirror),
+            b'-' => Ok(Tile::HSplitter),
+            b'|' => Ok(Tile::VSplitter),
+            _ => Err(format!("Invalid tile type: {}", value as char)),
+        }
+    }
+}
+
+impl From<Tile> for char {
+    fn from(value: Tile) -> Self {
+        match value {
+            Tile::Empty => '.',
+            Tile::NEMirror => '/',
+            Tile::NWMirror => '\\',
+            Tile::HSplitter => '-',
+            Tile::VSplitter => '|',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Right,
+    Left,
+    Up,
+    Down,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Left,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    /// Directions a beam travelling this way leaves the given tile in.
+    pub fn through(self, tile: Tile) -> ArrayVec<Direction, 2> {
+        match (tile, self) {
+            (Tile::HSplitter, Direction::Up | Direction::Down) => {
+                ArrayVec::from([Direction::Right, Direction::Left])
+            }
+            (Tile::VSplitter, Direction::Right | Direction::Left) => {
+                ArrayVec::from([Direction::Up, Direction::Down])
+            }
+            (Tile::NEMirror, _) => ArrayVec::from_iter([self.ne_mirror()]),
+            (Tile::NWMirror, _) => ArrayVec::from_iter([self.nw_mirror()]),
+            _ => ArrayVec::from_iter([self]),
+        }
+    }
+
+    fn ne_mirror(self) -> Self {
+        match self {
+            Direction::Right => Direction::Up,
+            Direction::Left => Direction::Down,
+            Direction::Up => Direction::Right,
+            Direction::Down => Direction::Left,
+        }
+    }
+
+    fn nw_mirror(self) -> Self {
+        match self {
+            Direction::Right => Direction::Down,
+            Direction::Left => Direction::Up,
+            Direction::Up => Direction::Left,
+            Direction::Down => Direction::Right,
+        }
+    }
+}
+
+/// A beam entering the tile at `position`, travelling in `direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Beam {
+    pub direction: Direction,
+    pub position: (usize, usize),
+}
+
+impl Beam {
+    pub fn new(position: (usize, usize), direction: Direction) -> Self {
+        Self {
+            direction,
+            position,
+        }
+    }
+
+    /// The beam in the neighbouring tile, or `None` if it leaves the grid.
+    pub fn next(mut self, rows: usize, cols: usize) -> Option<Self> {
+        let (row, col) = &mut self.position;
+        match self.direction {
+            Direction::Right if *col + 1 < cols => *col += 1,
+            Direction::Left if *col > 0 => *col -= 1,
+            Direction::Up if *row > 0 => *row -= 1,
+            Direction::Down if *row + 1 < rows => *row += 1,
+            _ => return None,
+        }
+        Some(self)
+    }
+}
+
+/// Set of tile positions stored as a bitmap in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TileSet {
+    cols: usize,
+    words: Vec<u64>,
+}
+
+impl TileSet {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            cols,
+            words: vec![0; (rows * cols).div_ceil(64)],
+        }
+    }
+
+    pub fn insert(&mut self, (row, col): (usize, usize)) {
+        let n = row * self.cols + col;
+        self.words[n / 64] |= 1 << (n % 64);
+    }
+
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        let n = row * self.cols + col;
+        self.words[n / 64] & (1 << (n % 64)) != 0
+    }
+
+    pub fn union_with(&mut self, other: &TileSet) {
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a |= b;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Positions in the set in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(n, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    let index = n * 64 + bit;
+                    (index / self.cols, index % self.cols)
+                })
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Contraption {
+    grid: Grid<Tile>,
+}
+
+impl Contraption {
+    pub fn parse(input: &str) -> Self {
+        let lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        let width = lines[0].len();
+        let grid = Grid::from_vec(
+            lines
+                .into_iter()
+                .flatten()
+                .copied()
+                .map(|c| Tile::try_from(c).unwrap())
+                .collect(),
+            width,
+        );
+        Self { grid }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.grid.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.grid.cols()
+    }
+
+    pub fn tile(&self, position: (usize, usize)) -> Tile {
+        self.grid[position]
+    }
+
+    /// Every beam which can enter the contraption from one of its edges.
+    pub fn edge_beams(&self) -> impl Iterator<Item = Beam> + '_ {
+        let (rows, cols) = (self.rows(), self.cols());
+        chain![
+            (0..rows).map(|row| Beam::new((row, 0), Direction::Right)),
+            (0..rows).map(move |row| Beam::new((row, cols - 1), Direction::Left)),
+            (0..cols).map(|col| Beam::new((0, col), Direction::Down)),
+            (0..cols).map(move |col| Beam::new((rows - 1, col), Direction::Up)),
+        ]
+    }
+
+    /// Beams leaving the tile a beam enters, already moved into their next tile.
+    pub fn step(&self, beam: Beam) -> impl Iterator<Item = Beam> + '_ {
+        beam.direction
+            .through(self.grid[beam.position])
+            .into_iter()
+            .filter_map(move |direction| {
+                Beam::new(beam.position, direction).next(self.rows(), self.cols())
+            })
+    }
+
+    fn is_split(&self, beam: Beam) -> bool {
+        beam.direction.through(self.grid[beam.position]).len() == 2
+    }
+
+    /// Follow a beam through mirrors and pass-through splitters, marking the
+    /// tiles it crosses. Returns the splitter which splits it, if any.
+    fn trace_segment(&self, start: Beam, tiles: &mut TileSet) -> Option<(usize, usize)> {
+        let mut beam = start;
+        loop {
+            tiles.insert(beam.position);
+            if self.is_split(beam) {
+                return Some(beam.position);
+            }
+            let direction = beam.direction.through(self.grid[beam.position])[0];
+            beam = Beam::new(beam.position, direction).next(self.rows(), self.cols())?;
+            if beam == start {
+                return None;
+            }
+        }
+    }
+}
+
+/// Energized tiles for every entry beam, computed once per group of splitters.
+///
+/// Each splitter which splits a beam behaves the same however the beam got
+/// there, so the contraption collapses to a graph of splitters joined by the
+/// segments between them. Splitters in the same strongly connected component
+/// energize the same tiles, which are shared by everything leading into them.
+#[derive(Debug, Clone)]
+pub struct EnergyMap<'a> {
+    contraption: &'a Contraption,
+    /// Component index for each splitter position.
+    components: HashMap<(usize, usize), usize>,
+    /// Tiles energized by a beam reaching any splitter in the component.
+    energized: Vec<TileSet>,
+}
+
+impl<'a> EnergyMap<'a> {
+    pub fn new(contraption: &'a Contraption) -> Self {
+        let (rows, cols) = (contraption.rows(), contraption.cols());
+        let splitters = contraption
+            .grid
+            .indexed_iter()
+            .filter(|(_, tile)| matches!(tile, Tile::HSplitter | Tile::VSplitter))
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+
+        // Tiles covered by the segments leaving each splitter, and the
+        // splitters at the end of those segments.
+        let segments = splitters
+            .iter()
+            .map(|&position| {
+                let mut tiles = TileSet::new(rows, cols);
+                tiles.insert(position);
+                let direction = match contraption.grid[position] {
+                    Tile::HSplitter => Direction::Up,
+                    _ => Direction::Right,
+                };
+                let next = contraption
+                    .step(Beam::new(position, direction))
+                    .filter_map(|beam| contraption.trace_segment(beam, &mut tiles))
+                    .collect::<ArrayVec<_, 2>>();
+                (position, (tiles, next))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let sccs =
+            strongly_connected_components(&splitters, |position| segments[position].1.clone());
+        let components = sccs
+            .iter()
+            .enumerate()
+            .flat_map(|(n, scc)| scc.iter().map(move |position| (*position, n)))
+            .collect::<HashMap<_, _>>();
+
+        let mut energized = vec![None; sccs.len()];
+        for n in 0..sccs.len() {
+            fill_component(n, &sccs, &segments, &components, &mut energized);
+        }
+
+        Self {
+            contraption,
+            components,
+            energized: energized.into_iter().map(Option::unwrap).collect(),
+        }
+    }
+
+    pub fn energized(&self, start: Beam) -> TileSet {
+        let mut tiles = TileSet::new(self.contraption.rows(), self.contraption.cols());
+        if let Some(splitter) = self.contraption.trace_segment(start, &mut tiles) {
+            tiles.union_with(&self.energized[self.components[&splitter]]);
+        }
+        tiles
+    }
+
+    pub fn energized_count(&self, start: Beam) -> usize {
+        self.energized(start).len()
+    }
+
+    /// The largest number of tiles energized by any beam entering from an edge.
+    pub fn max_energized_count(&self) -> usize {
+        self.contraption
+            .edge_beams()
+            .map(|beam| self.energized_count(beam))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+type Segments = HashMap<(usize, usize), (TileSet, ArrayVec<(usize, usize), 2>)>;
+
+fn fill_component(
+    n: usize,
+    sccs: &[Vec<(usize, usize)>],
+    segments: &Segments,
+    components: &HashMap<(usize, usize), usize>,
+    energized: &mut Vec<Option<TileSet>>,
+) {
+    if energized[n].is_some() {
+        return;
+    }
+    let mut tiles = segments[&sccs[n][0]].0.clone();
+    for position in &sccs[n] {
+        let (own, next) = &segments[position];
+        tiles.union_with(own);
+        for successor in next.iter().map(|p| components[p]).filter(|m| *m != n) {
+            fill_component(successor, sccs, segments, components, energized);
+            tiles.union_with(energized[successor].as_ref().unwrap());
+        }
+    }
+    energized[n] = Some(tiles);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn energized_from_top_left() {
+        let contraption = Contraption::parse(EXAMPLE);
+        let map = EnergyMap::new(&contraption);
+        assert_eq!(map.energized_count(Beam::new((0, 0), Direction::Right)), 46);
+    }
+
+    #[test]
+    fn best_entry() {
+        let contraption = Contraption::parse(EXAMPLE);
+        let map = EnergyMap::new(&contraption);
+        assert_eq!(map.energized_count(Beam::new((0, 3), Direction::Down)), 51);
+        assert_eq!(map.max_energized_count(), 51);
+    }
+
+    #[test]
+    fn tile_set() {
+        let mut tiles = TileSet::new(3, 40);
+        tiles.insert((0, 1));
+        tiles.insert((2, 39));
+        tiles.insert((1, 30));
+        assert_eq!(tiles.len(), 3);
+        assert!(tiles.contains((1, 30)));
+        assert!(!tiles.contains((1, 31)));
+        assert_eq!(
+            tiles.iter().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 30), (2, 39)]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+pub mod beam_trace;
+pub mod contraption;
 pub mod part1;
 pub mod part2;
 
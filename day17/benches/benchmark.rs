@@ -1,4 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day17::crucible::{Backend, CityMap, Crucible};
 use day17::{part1, part2, INPUT};
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -9,6 +10,21 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("solve day17 part2", |b| {
         b.iter(|| part2::solve(black_box(INPUT)));
     });
+
+    let map = CityMap::parse(INPUT);
+    let mut group = c.benchmark_group("best_route day17");
+    for (name, crucible) in [
+        ("part1", Crucible::standard()),
+        ("part2", Crucible::ultra()),
+    ] {
+        for backend in [Backend::AStar, Backend::BucketQueue] {
+            let crucible = crucible.with_backend(backend);
+            group.bench_function(format!("{name} {backend:?}"), |b| {
+                b.iter(|| crucible.best_route(black_box(&map)));
+            });
+        }
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
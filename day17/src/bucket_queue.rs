@@ -0,0 +1,100 @@
+/// Dijkstra's algorithm with a circular bucket queue (Dial's algorithm), for
+/// graphs with dense `usize` states and small integer edge costs.
+///
+/// - `start` is the starting state, less than `state_count`.
+/// - `max_cost` is the largest cost of any single edge.
+/// - `successors` pushes `(state, cost)` pairs for a state onto the buffer.
+/// - `success` checks whether the goal has been reached.
+///
+/// Returns the path from `start` to the first goal state found, and its cost.
+pub fn dijkstra<FN, FS>(
+    start: usize,
+    state_count: usize,
+    max_cost: usize,
+    mut successors: FN,
+    mut success: FS,
+) -> Option<(Vec<usize>, usize)>
+where
+    FN: FnMut(usize, &mut Vec<(usize, usize)>),
+    FS: FnMut(usize) -> bool,
+{
+    let mut distances = vec![usize::MAX; state_count];
+    let mut parents = vec![usize::MAX; state_count];
+    let size = max_cost + 1;
+    let mut buckets = vec![vec![]; size];
+    let mut pending = 1;
+    let mut buffer = vec![];
+
+    distances[start] = 0;
+    buckets[0].push(start);
+
+    let mut cost = 0;
+    while pending > 0 {
+        let bucket = cost % size;
+        while let Some(state) = buckets[bucket].pop() {
+            pending -= 1;
+            if distances[state] != cost {
+                continue;
+            }
+            if success(state) {
+                return Some((path(&parents, start, state), cost));
+            }
+            buffer.clear();
+            successors(state, &mut buffer);
+            for &(next, step_cost) in &buffer {
+                debug_assert!(step_cost <= max_cost);
+                let next_cost = cost + step_cost;
+                if next_cost < distances[next] {
+                    distances[next] = next_cost;
+                    parents[next] = state;
+                    buckets[next_cost % size].push(next);
+                    pending += 1;
+                }
+            }
+        }
+        cost += 1;
+    }
+
+    None
+}
+
+fn path(parents: &[usize], start: usize, end: usize) -> Vec<usize> {
+    let mut path = vec![end];
+    let mut state = end;
+    while state != start {
+        state = parents[state];
+        path.push(state);
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortest_path() {
+        // 0 -> 1 -> 3 costs 4, 0 -> 2 -> 3 costs 3, 0 -> 3 costs 5.
+        let edges = [
+            vec![(1, 1), (2, 2), (3, 5)],
+            vec![(3, 3)],
+            vec![(3, 1)],
+            vec![],
+        ];
+        let result = dijkstra(
+            0,
+            4,
+            5,
+            |state, buffer| buffer.extend(&edges[state]),
+            |state| state == 3,
+        );
+        assert_eq!(result, Some((vec![0, 2, 3], 3)));
+    }
+
+    #[test]
+    fn unreachable() {
+        let result = dijkstra(0, 2, 1, |_, _| (), |state| state == 1);
+        assert_eq!(result, None);
+    }
+}
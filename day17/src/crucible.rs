@@ -0,0 +1,484 @@
+use crate::bucket_queue;
+use grid::Grid;
+use pathfinding::directed::astar;
+
+#[derive(Debug, Clone)]
+pub struct CityMap {
+    grid: Grid<u8>,
+}
+
+impl CityMap {
+    pub fn parse(input: &str) -> Self {
+        let lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        let cols = lines[0].len();
+        let grid = Grid::from_vec(
+            lines.into_iter().flatten().map(|c| *c - b'0').collect(),
+            cols,
+        );
+        Self { grid }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.grid.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.grid.cols()
+    }
+
+    /// Heat lost by entering the block at the given position.
+    pub fn heat_loss(&self, position: (usize, usize)) -> u8 {
+        self.grid[position]
+    }
+
+    /// The bottom right block, where the crucible is delivered.
+    pub fn factory(&self) -> (usize, usize) {
+        (self.rows() - 1, self.cols() - 1)
+    }
+
+    /// Draw the route on the map as in the puzzle text, with an arrow on
+    /// every block entered and the heat loss digit everywhere else.
+    pub fn render(&self, route: &Route) -> String {
+        let mut output = self
+            .grid
+            .iter_rows()
+            .map(|row| row.map(|c| (c + b'0') as char).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut position = route.start();
+        for step in &route.moves {
+            for _ in 0..step.steps {
+                position = step
+                    .direction
+                    .step(position, self.rows(), self.cols())
+                    .unwrap();
+                output[position.0][position.1] = step.direction.arrow();
+            }
+        }
+        output
+            .into_iter()
+            .flat_map(|row| row.into_iter().chain(['\n']))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (-1, 1),
+            Direction::DownLeft => (1, -1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+
+    fn from_delta(delta: (isize, isize)) -> Self {
+        match (delta.0.signum(), delta.1.signum()) {
+            (-1, 0) => Direction::Up,
+            (1, 0) => Direction::Down,
+            (0, -1) => Direction::Left,
+            (0, 1) => Direction::Right,
+            (-1, -1) => Direction::UpLeft,
+            (-1, 1) => Direction::UpRight,
+            (1, -1) => Direction::DownLeft,
+            (1, 1) => Direction::DownRight,
+            _ => panic!("Invalid move: {:?}", delta),
+        }
+    }
+
+    fn axis(self) -> Axis {
+        match self {
+            Direction::Up | Direction::Down => Axis::Vertical,
+            Direction::Left | Direction::Right => Axis::Horizontal,
+            Direction::UpLeft | Direction::DownRight => Axis::Diagonal,
+            Direction::UpRight | Direction::DownLeft => Axis::AntiDiagonal,
+        }
+    }
+
+    fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+            Direction::UpLeft | Direction::DownRight => '\\',
+            Direction::UpRight | Direction::DownLeft => '/',
+        }
+    }
+
+    /// The neighbouring position, or `None` if it is off the map.
+    fn step(self, (row, col): (usize, usize), rows: usize, cols: usize) -> Option<(usize, usize)> {
+        let (dr, dc) = self.delta();
+        let row = row.checked_add_signed(dr).filter(|r| *r < rows)?;
+        let col = col.checked_add_signed(dc).filter(|c| *c < cols)?;
+        Some((row, col))
+    }
+}
+
+/// Line of movement. The crucible must turn onto a different axis after each move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Axis {
+    Horizontal,
+    Vertical,
+    Diagonal,
+    AntiDiagonal,
+}
+
+impl Axis {
+    const ALL: [Axis; 4] = [
+        Axis::Horizontal,
+        Axis::Vertical,
+        Axis::Diagonal,
+        Axis::AntiDiagonal,
+    ];
+
+    fn directions(self) -> [Direction; 2] {
+        match self {
+            Axis::Horizontal => [Direction::Left, Direction::Right],
+            Axis::Vertical => [Direction::Up, Direction::Down],
+            Axis::Diagonal => [Direction::UpLeft, Direction::DownRight],
+            Axis::AntiDiagonal => [Direction::UpRight, Direction::DownLeft],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Node {
+    Start((usize, usize)),
+    Moved((usize, usize), Axis),
+}
+
+impl Node {
+    fn position(self) -> (usize, usize) {
+        match self {
+            Node::Start(position) | Node::Moved(position, _) => position,
+        }
+    }
+
+    /// Dense index of a moved node, with a spare index per block for the start.
+    fn index(self, cols: usize) -> usize {
+        let ((row, col), slot) = match self {
+            Node::Start(position) => (position, Axis::ALL.len()),
+            Node::Moved(position, axis) => (position, axis as usize),
+        };
+        (row * cols + col) * (Axis::ALL.len() + 1) + slot
+    }
+
+    fn from_index(index: usize, cols: usize) -> Self {
+        let (block, slot) = (index / (Axis::ALL.len() + 1), index % (Axis::ALL.len() + 1));
+        let position = (block / cols, block % cols);
+        match Axis::ALL.get(slot) {
+            Some(axis) => Node::Moved(position, *axis),
+            None => Node::Start(position),
+        }
+    }
+}
+
+/// Shortest path algorithm used to find routes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// A* search from the `pathfinding` crate.
+    #[default]
+    AStar,
+    /// Dijkstra's algorithm with a bucket queue over a dense state array.
+    BucketQueue,
+}
+
+/// A straight move of one or more blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub direction: Direction,
+    pub steps: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub heat_loss: usize,
+    pub moves: Vec<Move>,
+    /// Every block on the route, starting with the start block.
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Route {
+    pub fn start(&self) -> (usize, usize) {
+        self.cells[0]
+    }
+
+    pub fn end(&self) -> (usize, usize) {
+        self.cells[self.cells.len() - 1]
+    }
+}
+
+/// A crucible which must move between `min_steps` and `max_steps` blocks in a
+/// straight line before turning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crucible {
+    pub min_steps: usize,
+    pub max_steps: usize,
+    /// Also allow moves along diagonals.
+    pub diagonal: bool,
+    pub backend: Backend,
+}
+
+impl Crucible {
+    pub fn new(min_steps: usize, max_steps: usize) -> Self {
+        assert!(
+            0 < min_steps && min_steps <= max_steps,
+            "invalid step bounds: {min_steps}..={max_steps}"
+        );
+        Self {
+            min_steps,
+            max_steps,
+            diagonal: false,
+            backend: Backend::AStar,
+        }
+    }
+
+    /// The crucible from part 1.
+    pub fn standard() -> Self {
+        Self::new(1, 3)
+    }
+
+    /// The ultra crucible from part 2.
+    pub fn ultra() -> Self {
+        Self::new(4, 10)
+    }
+
+    pub fn with_diagonal(mut self, diagonal: bool) -> Self {
+        self.diagonal = diagonal;
+        self
+    }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Least heat loss route from the top left block to the factory.
+    pub fn best_route(&self, map: &CityMap) -> Option<Route> {
+        self.find_route(map, (0, 0), map.factory())
+    }
+
+    pub fn find_route(
+        &self,
+        map: &CityMap,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Option<Route> {
+        let (nodes, heat_loss) = match self.backend {
+            Backend::AStar => astar::astar(
+                &Node::Start(start),
+                |&node| {
+                    let mut states = vec![];
+                    self.successors(map, node, |next, cost| states.push((next, cost)));
+                    states
+                },
+                |&node| self.distance(node.position(), end),
+                |&node| node.position() == end,
+            )?,
+            Backend::BucketQueue => {
+                let cols = map.cols();
+                let (indices, heat_loss) = bucket_queue::dijkstra(
+                    Node::Start(start).index(cols),
+                    map.rows() * cols * (Axis::ALL.len() + 1),
+                    // Every block loses at most 9 heat.
+                    9 * self.max_steps,
+                    |index, buffer| {
+                        self.successors(map, Node::from_index(index, cols), |next, cost| {
+                            buffer.push((next.index(cols), cost))
+                        })
+                    },
+                    |index| Node::from_index(index, cols).position() == end,
+                )?;
+                let nodes = indices
+                    .into_iter()
+                    .map(|index| Node::from_index(index, cols))
+                    .collect();
+                (nodes, heat_loss)
+            }
+        };
+        Some(self.route(nodes, heat_loss))
+    }
+
+    fn axes(&self) -> &'static [Axis] {
+        if self.diagonal {
+            &Axis::ALL
+        } else {
+            &[Axis::Horizontal, Axis::Vertical]
+        }
+    }
+
+    fn successors(&self, map: &CityMap, node: Node, mut push: impl FnMut(Node, usize)) {
+        let (position, last_axis) = match node {
+            Node::Start(position) => (position, None),
+            Node::Moved(position, axis) => (position, Some(axis)),
+        };
+        for axis in self.axes().iter().filter(|axis| Some(**axis) != last_axis) {
+            for direction in axis.directions() {
+                let mut current = position;
+                let mut cost = 0;
+                for n in 1..=self.max_steps {
+                    let Some(next) = direction.step(current, map.rows(), map.cols()) else {
+                        break;
+                    };
+                    current = next;
+                    cost += map.heat_loss(current) as usize;
+                    if n >= self.min_steps {
+                        push(Node::Moved(current, *axis), cost);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lower bound on the number of blocks to enter to reach the target.
+    fn distance(&self, (r1, c1): (usize, usize), (r2, c2): (usize, usize)) -> usize {
+        let (dr, dc) = (r1.abs_diff(r2), c1.abs_diff(c2));
+        if self.diagonal {
+            dr.max(dc)
+        } else {
+            dr + dc
+        }
+    }
+
+    fn route(&self, nodes: Vec<Node>, heat_loss: usize) -> Route {
+        let mut moves = vec![];
+        let mut cells = vec![nodes[0].position()];
+        for pair in nodes.windows(2) {
+            let (from, to) = (pair[0].position(), pair[1].position());
+            let delta = (
+                to.0 as isize - from.0 as isize,
+                to.1 as isize - from.1 as isize,
+            );
+            let direction = Direction::from_delta(delta);
+            debug_assert!(matches!(pair[1], Node::Moved(_, axis) if axis == direction.axis()));
+            let steps = delta.0.unsigned_abs().max(delta.1.unsigned_abs());
+            cells.extend((1..=steps as isize).map(|n| {
+                (
+                    from.0.wrapping_add_signed(n * delta.0.signum()),
+                    from.1.wrapping_add_signed(n * delta.1.signum()),
+                )
+            }));
+            moves.push(Move { direction, steps });
+        }
+        Route {
+            heat_loss,
+            moves,
+            cells,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+    const EXAMPLE2: &str = include_str!("../example2.txt");
+
+    const EXAMPLE2_ROUTE: &str = "\
+1>>>>>>>1111
+9999999v9991
+9999999v9991
+9999999v9991
+9999999v>>>>
+";
+
+    #[test]
+    fn standard_route() {
+        let map = CityMap::parse(EXAMPLE);
+        let route = Crucible::standard().best_route(&map).unwrap();
+        assert_eq!(route.heat_loss, 102);
+        assert_eq!(route.start(), (0, 0));
+        assert_eq!(route.end(), (12, 12));
+        assert_eq!(
+            route.moves.iter().map(|m| m.steps).sum::<usize>(),
+            route.cells.len() - 1
+        );
+        assert_eq!(
+            route.cells[1..]
+                .iter()
+                .map(|p| map.heat_loss(*p) as usize)
+                .sum::<usize>(),
+            102
+        );
+        assert_eq!(map.render(&route).lines().count(), 13);
+    }
+
+    #[test]
+    fn ultra_route() {
+        let map = CityMap::parse(EXAMPLE2);
+        let route = Crucible::ultra().best_route(&map).unwrap();
+        assert_eq!(route.heat_loss, 71);
+        assert_eq!(map.render(&route), EXAMPLE2_ROUTE);
+        assert_eq!(
+            route.moves,
+            vec![
+                Move {
+                    direction: Direction::Right,
+                    steps: 7
+                },
+                Move {
+                    direction: Direction::Down,
+                    steps: 4
+                },
+                Move {
+                    direction: Direction::Right,
+                    steps: 4
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_endpoints_and_diagonal() {
+        let map = CityMap::parse(EXAMPLE);
+        let route = Crucible::standard()
+            .find_route(&map, (0, 0), (0, 2))
+            .unwrap();
+        assert_eq!(route.heat_loss, 5);
+
+        let diagonal = Crucible::standard().with_diagonal(true);
+        let route = diagonal.best_route(&map).unwrap();
+        assert!(route.heat_loss < 102);
+        assert!(route
+            .moves
+            .windows(2)
+            .all(|pair| pair[0].direction.axis() != pair[1].direction.axis()));
+        assert!(route.moves.iter().all(|m| (1..=3).contains(&m.steps)));
+    }
+
+    #[test]
+    fn bucket_queue_backend() {
+        for (input, crucible) in [
+            (EXAMPLE, Crucible::standard()),
+            (EXAMPLE, Crucible::ultra()),
+            (EXAMPLE2, Crucible::ultra()),
+            (EXAMPLE, Crucible::standard().with_diagonal(true)),
+        ] {
+            let map = CityMap::parse(input);
+            let expected = crucible.best_route(&map).unwrap();
+            let route = crucible
+                .with_backend(Backend::BucketQueue)
+                .best_route(&map)
+                .unwrap();
+            assert_eq!(route.heat_loss, expected.heat_loss);
+            assert_eq!(route.end(), map.factory());
+        }
+    }
+}
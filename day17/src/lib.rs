@@ -1,3 +1,5 @@
+pub mod bucket_queue;
+pub mod crucible;
 pub mod part1;
 pub mod part2;
 
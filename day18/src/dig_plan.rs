@@ -0,0 +1,391 @@
+use itertools::Itertools;
+use std::fmt::{self, Write};
+
+/// Largest image `render_ppm` will draw, in pixels.
+pub const MAX_PIXELS: u64 = 1 << 24;
+
+/// Why a dig plan can't be measured or drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The trench doesn't end back at the origin.
+    NotClosed,
+    /// The trench crosses or runs back over itself, so it doesn't outline a
+    /// single lagoon.
+    SelfIntersecting,
+    /// The image would have more than [`MAX_PIXELS`] pixels.
+    TooLarge { width: u64, height: u64 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotClosed => write!(f, "the trench doesn't return to the origin"),
+            Error::SelfIntersecting => write!(f, "the trench crosses itself"),
+            Error::TooLarge { width, height } => {
+                write!(f, "a {}x{} image is too large to draw", width, height)
+            }
+        }
+    }
+}
+
+/// How the direction and distance of each instruction are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// The `R 6` part of each line.
+    Plain,
+    /// The hexadecimal colour code from part 2.
+    Hex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Position {
+    fn moved(self, direction: Direction, distance: i64) -> Self {
+        match direction {
+            Direction::Up => Self {
+                y: self.y + distance,
+                ..self
+            },
+            Direction::Down => Self {
+                y: self.y - distance,
+                ..self
+            },
+            Direction::Left => Self {
+                x: self.x - distance,
+                ..self
+            },
+            Direction::Right => Self {
+                x: self.x + distance,
+                ..self
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub direction: Direction,
+    pub distance: i64,
+    /// The colour recorded for the trench, as `0xRRGGBB`.
+    pub colour: u32,
+}
+
+impl Instruction {
+    pub fn parse(line: &str, encoding: Encoding) -> Self {
+        let (plain, hex) = line.split_once(" (#").unwrap();
+        let hex = hex.trim_end_matches(')');
+        let colour = u32::from_str_radix(hex, 16).unwrap();
+        let (direction, distance) = match encoding {
+            Encoding::Plain => {
+                let (direction, distance) = plain.split_once(' ').unwrap();
+                let direction = match direction {
+                    "U" => Direction::Up,
+                    "D" => Direction::Down,
+                    "L" => Direction::Left,
+                    "R" => Direction::Right,
+                    _ => panic!("Unknown direction: {}", direction),
+                };
+                (direction, distance.parse().unwrap())
+            }
+            Encoding::Hex => {
+                let direction = match &hex[5..6] {
+                    "3" => Direction::Up,
+                    "1" => Direction::Down,
+                    "2" => Direction::Left,
+                    "0" => Direction::Right,
+                    other => panic!("Unknown direction: {}", other),
+                };
+                (direction, i64::from_str_radix(&hex[0..5], 16).unwrap())
+            }
+        };
+        Self {
+            direction,
+            distance,
+            colour,
+        }
+    }
+}
+
+/// The dig plan as a polygon through the centres of the trench cubes,
+/// starting and ending at the origin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigPlan {
+    instructions: Vec<Instruction>,
+    vertices: Vec<Position>,
+}
+
+impl DigPlan {
+    pub fn parse(input: &str, encoding: Encoding) -> Self {
+        Self::new(
+            input
+                .lines()
+                .map(|line| Instruction::parse(line, encoding))
+                .collect(),
+        )
+    }
+
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        let vertices = instructions
+            .iter()
+            .scan(Position { x: 0, y: 0 }, |position, instruction| {
+                *position = position.moved(instruction.direction, instruction.distance);
+                Some(*position)
+            })
+            .collect();
+        Self {
+            instructions,
+            vertices,
+        }
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// The corner at the end of each instruction. The last one is back at the
+    /// origin for a closed loop.
+    pub fn vertices(&self) -> &[Position] {
+        &self.vertices
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.vertices.last() == Some(&Position { x: 0, y: 0 })
+    }
+
+    /// Area enclosed by the polygon, using the shoelace formula.
+    pub fn interior_area(&self) -> u64 {
+        let (sum1, sum2) = self
+            .vertices
+            .iter()
+            .circular_tuple_windows()
+            .fold((0, 0), |(sum1, sum2), (current, next)| {
+                (sum1 + current.x * next.y, sum2 + current.y * next.x)
+            });
+        sum1.abs_diff(sum2) / 2
+    }
+
+    /// Number of lattice points on the boundary, which is the number of
+    /// cubes dug out for the trench.
+    pub fn boundary_points(&self) -> u64 {
+        self.instructions
+            .iter()
+            .map(|instruction| instruction.distance as u64)
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside the polygon, by Pick's theorem,
+    /// which only holds for a closed trench that doesn't cross itself.
+    pub fn interior_points(&self) -> Result<u64, Error> {
+        if !self.is_closed() {
+            return Err(Error::NotClosed);
+        }
+        if self.is_self_intersecting() {
+            return Err(Error::SelfIntersecting);
+        }
+        Ok(self.interior_area() + 1 - self.boundary_points() / 2)
+    }
+
+    /// Cubic metres of lava the lagoon holds: the trench plus its interior.
+    pub fn volume(&self) -> Result<u64, Error> {
+        Ok(self.interior_points()? + self.boundary_points())
+    }
+
+    /// Check whether the trench crosses, touches or runs back over itself
+    /// anywhere other than at the corners joining consecutive instructions.
+    pub fn is_self_intersecting(&self) -> bool {
+        let segments = self.segments().collect::<Vec<_>>();
+        let count = segments.len();
+        (0..count).tuple_combinations().any(|(i, j)| {
+            let adjacent = j == i + 1 || (self.is_closed() && i == 0 && j == count - 1);
+            if adjacent {
+                // Consecutive segments only share a corner, unless the second
+                // one turns back along the first.
+                let (a, b) = (&self.instructions[i], &self.instructions[j]);
+                is_reverse(a.direction, b.direction)
+            } else {
+                overlaps(segments[i], segments[j])
+            }
+        })
+    }
+
+    /// The trench as an SVG image, with each instruction drawn in its colour.
+    pub fn render_svg(&self) -> String {
+        let (min, max) = self.bounds();
+        let (width, height) = (max.x - min.x + 1, max.y - min.y + 1);
+        let stroke = (width.max(height) / 200).max(1);
+        let mut output = String::new();
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            min.x - 1,
+            -max.y - 1,
+            width + 1,
+            height + 1
+        )
+        .unwrap();
+        for ((start, end), instruction) in self.segments().zip(self.instructions.iter()) {
+            writeln!(
+                output,
+                r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#{:06x}" stroke-width="{}" stroke-linecap="square"/>"##,
+                start.x, -start.y, end.x, -end.y, instruction.colour, stroke
+            )
+            .unwrap();
+        }
+        output.push_str("</svg>\n");
+        output
+    }
+
+    /// The trench as a binary PPM image with one pixel per cube, in the
+    /// colour recorded for it. Fails for images over [`MAX_PIXELS`], such as
+    /// those of hex encoded plans.
+    pub fn render_ppm(&self) -> Result<Vec<u8>, Error> {
+        let (min, max) = self.bounds();
+        let (width, height) = ((max.x - min.x + 1) as u64, (max.y - min.y + 1) as u64);
+        if width.saturating_mul(height) > MAX_PIXELS {
+            return Err(Error::TooLarge { width, height });
+        }
+        let (width, height) = (width as usize, height as usize);
+        let mut pixels = vec![0; width * height * 3];
+        for ((start, _), instruction) in self.segments().zip(self.instructions.iter()) {
+            for n in 1..=instruction.distance {
+                let position = start.moved(instruction.direction, n);
+                let (col, row) = ((position.x - min.x) as usize, (max.y - position.y) as usize);
+                let offset = (row * width + col) * 3;
+                pixels[offset..offset + 3].copy_from_slice(&instruction.colour.to_be_bytes()[1..]);
+            }
+        }
+        let mut output = format!("P6\n{width} {height}\n255\n").into_bytes();
+        output.extend(pixels);
+        Ok(output)
+    }
+
+    /// Start and end of each instruction.
+    fn segments(&self) -> impl Iterator<Item = (Position, Position)> + '_ {
+        std::iter::once(Position { x: 0, y: 0 })
+            .chain(self.vertices.iter().copied())
+            .tuple_windows()
+    }
+
+    fn bounds(&self) -> (Position, Position) {
+        self.vertices.iter().fold(
+            (Position { x: 0, y: 0 }, Position { x: 0, y: 0 }),
+            |(min, max), p| {
+                (
+                    Position {
+                        x: min.x.min(p.x),
+                        y: min.y.min(p.y),
+                    },
+                    Position {
+                        x: max.x.max(p.x),
+                        y: max.y.max(p.y),
+                    },
+                )
+            },
+        )
+    }
+}
+
+fn is_reverse(a: Direction, b: Direction) -> bool {
+    matches!(
+        (a, b),
+        (Direction::Up, Direction::Down)
+            | (Direction::Down, Direction::Up)
+            | (Direction::Left, Direction::Right)
+            | (Direction::Right, Direction::Left)
+    )
+}
+
+/// Check whether two axis aligned segments share any point.
+fn overlaps((a1, a2): (Position, Position), (b1, b2): (Position, Position)) -> bool {
+    a1.x.min(a2.x) <= b1.x.max(b2.x)
+        && b1.x.min(b2.x) <= a1.x.max(a2.x)
+        && a1.y.min(a2.y) <= b1.y.max(b2.y)
+        && b1.y.min(b2.y) <= a1.y.max(a2.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn plain_example() {
+        let plan = DigPlan::parse(EXAMPLE, Encoding::Plain);
+        assert!(plan.is_closed());
+        assert_eq!(plan.vertices()[0], Position { x: 6, y: 0 });
+        assert_eq!(plan.boundary_points(), 38);
+        assert_eq!(plan.interior_area(), 42);
+        assert_eq!(plan.interior_points(), Ok(24));
+        assert_eq!(plan.volume(), Ok(62));
+        assert_eq!(plan.instructions()[0].colour, 0x70c710);
+        assert!(!plan.is_self_intersecting());
+    }
+
+    #[test]
+    fn hex_example() {
+        let plan = DigPlan::parse(EXAMPLE, Encoding::Hex);
+        assert_eq!(plan.instructions()[0].distance, 461937);
+        assert_eq!(plan.instructions()[0].direction, Direction::Right);
+        assert_eq!(plan.volume(), Ok(952408144115));
+        assert!(!plan.is_self_intersecting());
+        assert_eq!(
+            plan.render_ppm(),
+            Err(Error::TooLarge {
+                width: 1186329,
+                height: 1186329
+            })
+        );
+    }
+
+    #[test]
+    fn self_intersection() {
+        let crossing = "R 2 (#000000)\nD 1 (#000000)\nL 1 (#000000)\nU 2 (#000000)\nL 1 (#000000)\nD 1 (#000000)";
+        assert!(DigPlan::parse(crossing, Encoding::Plain).is_self_intersecting());
+        let backtrack = "R 2 (#000000)\nL 1 (#000000)\nD 1 (#000000)\nL 1 (#000000)\nU 1 (#000000)";
+        assert!(DigPlan::parse(backtrack, Encoding::Plain).is_self_intersecting());
+    }
+
+    #[test]
+    fn errors() {
+        let flat = DigPlan::parse("R 2 (#000000)\nL 2 (#000000)", Encoding::Plain);
+        assert_eq!(flat.interior_area(), 0);
+        assert_eq!(flat.volume(), Err(Error::SelfIntersecting));
+        let open = DigPlan::parse("R 2 (#000000)\nD 2 (#000000)", Encoding::Plain);
+        assert_eq!(open.interior_points(), Err(Error::NotClosed));
+        assert_eq!(
+            Error::NotClosed.to_string(),
+            "the trench doesn't return to the origin"
+        );
+    }
+
+    #[test]
+    fn render() {
+        let plan = DigPlan::parse(EXAMPLE, Encoding::Plain);
+        let svg = plan.render_svg();
+        assert_eq!(svg.matches("<line").count(), 14);
+        assert!(svg.contains(r##"stroke="#70c710""##));
+
+        let ppm = plan.render_ppm().unwrap();
+        let header = b"P6\n7 10\n255\n";
+        assert!(ppm.starts_with(header));
+        assert_eq!(ppm.len(), header.len() + 7 * 10 * 3);
+        let lit = ppm[header.len()..]
+            .chunks(3)
+            .filter(|pixel| pixel.iter().any(|c| *c != 0))
+            .count();
+        assert_eq!(lit, 38);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod dig_plan;
 pub mod part1;
 pub mod part2;
 
@@ -0,0 +1,498 @@
+use crate::split_range::SplitRange;
+use crate::workflow::{
+    Action, Categories, Category, Condition, Error, Operator, Part, Workflows, START_WORKFLOW,
+};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Range of every rating in the puzzle, from 1 to 4000 inclusive.
+pub const RATING_RANGE: Range<u64> = 1..4001;
+
+/// A hyper-rectangle of ratings, with one range per [`Category`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub ranges: Vec<Range<u64>>,
+}
+
+impl Region {
+    /// The same range of ratings in every category.
+    pub fn uniform(categories: usize, range: Range<u64>) -> Self {
+        Self {
+            ranges: vec![range; categories],
+        }
+    }
+
+    pub fn range(&self, category: Category) -> &Range<u64> {
+        &self.ranges[category.0]
+    }
+
+    /// Number of distinct parts in the region.
+    pub fn combinations(&self) -> usize {
+        self.ranges
+            .iter()
+            .map(|range| (range.end - range.start) as usize)
+            .product()
+    }
+
+    pub fn contains(&self, part: &Part) -> bool {
+        self.ranges
+            .iter()
+            .zip(part.ratings.iter())
+            .all(|(range, rating)| range.contains(rating))
+    }
+
+    /// Split into the parts rated below `threshold` and the rest.
+    fn split(self, category: Category, threshold: u64) -> (Option<Self>, Option<Self>) {
+        let (below, above) = self.range(category).split(threshold);
+        let with = |range| {
+            let mut region = self.clone();
+            region.ranges[category.0] = range;
+            region
+        };
+        (below.map(with), above.map(with))
+    }
+
+    /// Split into the parts which meet the condition and those which do not.
+    pub fn partition(self, condition: &Condition) -> (Option<Self>, Vec<Self>) {
+        let Condition::Compare {
+            category,
+            operator,
+            value,
+        } = *condition
+        else {
+            return (Some(self), vec![]);
+        };
+        match operator {
+            Operator::Less => {
+                let (below, above) = self.split(category, value);
+                (below, above.into_iter().collect())
+            }
+            Operator::LessOrEqual => {
+                let (below, above) = self.split(category, value + 1);
+                (below, above.into_iter().collect())
+            }
+            Operator::Greater => {
+                let (below, above) = self.split(category, value + 1);
+                (above, below.into_iter().collect())
+            }
+            Operator::GreaterOrEqual => {
+                let (below, above) = self.split(category, value);
+                (above, below.into_iter().collect())
+            }
+            Operator::Equal => {
+                let (below, rest) = self.split(category, value);
+                let (equal, above) = match rest {
+                    Some(rest) => rest.split(category, value + 1),
+                    None => (None, None),
+                };
+                (equal, below.into_iter().chain(above).collect())
+            }
+        }
+    }
+
+    /// The ratings which are narrower than in `domain`, such as
+    /// `s<1351, 1416<=x<2663`, or `any` if there are none.
+    pub fn describe(&self, domain: &Region, categories: &Categories) -> String {
+        let constraints = categories
+            .iter()
+            .filter_map(|category| {
+                let (range, full) = (self.range(category), domain.range(category));
+                let name = categories.name(category);
+                match (range.start > full.start, range.end < full.end) {
+                    (false, false) => None,
+                    (true, false) => Some(format!("{}>={}", name, range.start)),
+                    (false, true) => Some(format!("{}<{}", name, range.end)),
+                    (true, true) => Some(format!("{}<={}<{}", range.start, name, range.end)),
+                }
+            })
+            .collect::<Vec<_>>();
+        if constraints.is_empty() {
+            String::from("any")
+        } else {
+            constraints.join(", ")
+        }
+    }
+}
+
+/// Normalised decision tree, where every test is `rating < threshold`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Accept,
+    Reject,
+    Split {
+        category: Category,
+        threshold: u64,
+        below: Box<Node>,
+        above: Box<Node>,
+    },
+}
+
+impl Node {
+    fn depth(&self) -> usize {
+        match self {
+            Node::Accept | Node::Reject => 0,
+            Node::Split { below, above, .. } => 1 + below.depth().max(above.depth()),
+        }
+    }
+}
+
+/// A rule in a workflow, by its position in the workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuleRef<'a> {
+    pub workflow: &'a str,
+    pub rule: usize,
+}
+
+/// Workflows and rules found to have no effect while compiling.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Analysis<'a> {
+    /// Workflows which no part can be sent to.
+    pub unreachable_workflows: Vec<&'a str>,
+    /// Rules which no part gets as far as, because earlier rules catch them all.
+    pub unreachable_rules: Vec<RuleRef<'a>>,
+    /// Rules which parts reach, but whose condition none of them meet.
+    pub dead_rules: Vec<RuleRef<'a>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecisionTree<'a> {
+    domain: Region,
+    root: Node,
+    analysis: Analysis<'a>,
+}
+
+impl<'a> DecisionTree<'a> {
+    /// Compile the workflows, starting from `in`, into a single decision tree
+    /// for the parts in the given region. Fails if any of those parts would be
+    /// sent round a loop of workflows, or to a workflow that doesn't exist.
+    pub fn compile(workflows: &Workflows<'a>, domain: Region) -> Result<Self, Error<'a>> {
+        let mut compiler = Compiler {
+            workflows,
+            rules: HashMap::new(),
+            stack: vec![],
+        };
+        let root = compiler.workflow(START_WORKFLOW, domain.clone())?;
+        let analysis = compiler.analysis();
+        Ok(Self {
+            domain,
+            root,
+            analysis,
+        })
+    }
+
+    /// The region of parts the tree was compiled for.
+    pub fn domain(&self) -> &Region {
+        &self.domain
+    }
+
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+
+    pub fn depth(&self) -> usize {
+        self.root.depth()
+    }
+
+    pub fn analysis(&self) -> &Analysis<'a> {
+        &self.analysis
+    }
+
+    /// Follow the tree from the root, making one comparison per level.
+    pub fn is_accepted(&self, part: &Part) -> bool {
+        let mut node = &self.root;
+        loop {
+            match node {
+                Node::Accept => return true,
+                Node::Reject => return false,
+                Node::Split {
+                    category,
+                    threshold,
+                    below,
+                    above,
+                } => {
+                    node = if part.rating(*category) < *threshold {
+                        below
+                    } else {
+                        above
+                    };
+                }
+            }
+        }
+    }
+
+    /// Disjoint regions which together hold every accepted part.
+    pub fn accepted_regions(&self) -> Vec<Region> {
+        let mut regions = vec![];
+        let mut stack = vec![(&self.root, self.domain.clone())];
+        while let Some((node, region)) = stack.pop() {
+            match node {
+                Node::Accept => regions.push(region),
+                Node::Reject => (),
+                Node::Split {
+                    category,
+                    threshold,
+                    below,
+                    above,
+                } => {
+                    let (lower, upper) = region.split(*category, *threshold);
+                    if let Some(region) = upper {
+                        stack.push((above, region));
+                    }
+                    if let Some(region) = lower {
+                        stack.push((below, region));
+                    }
+                }
+            }
+        }
+        regions
+    }
+
+    /// Number of distinct accepted parts.
+    pub fn accepted_combinations(&self) -> usize {
+        self.accepted_regions()
+            .iter()
+            .map(Region::combinations)
+            .sum()
+    }
+}
+
+struct Compiler<'w, 'a> {
+    workflows: &'w Workflows<'a>,
+    /// Rules reached by any part, and whether any of those parts met the condition.
+    rules: HashMap<RuleRef<'a>, bool>,
+    /// Workflows being compiled, to detect cycles.
+    stack: Vec<&'a str>,
+}
+
+impl<'a> Compiler<'_, 'a> {
+    fn workflow(&mut self, id: &'a str, region: Region) -> Result<Node, Error<'a>> {
+        if self.stack.contains(&id) {
+            return Err(Error::Cycle { workflow: id });
+        }
+        self.stack.push(id);
+        let node = self.rule(
+            RuleRef {
+                workflow: id,
+                rule: 0,
+            },
+            region,
+        )?;
+        self.stack.pop();
+        Ok(node)
+    }
+
+    fn rule(&mut self, rule_ref: RuleRef<'a>, region: Region) -> Result<Node, Error<'a>> {
+        let workflow = self
+            .workflows
+            .get(rule_ref.workflow)
+            .ok_or(Error::Missing {
+                workflow: rule_ref.workflow,
+            })?;
+        let rule = &workflow.rules[rule_ref.rule];
+        self.rules.entry(rule_ref).or_insert(false);
+
+        let condition = rule.condition;
+        let Condition::Compare { category, .. } = condition else {
+            return self.pass(rule_ref, region);
+        };
+
+        // The pieces only differ in `category`, so in order of that range
+        // each one starts at the threshold separating it from the last.
+        let (pass, fail) = region.partition(&condition);
+        let mut pieces = pass
+            .map(|region| (region, true))
+            .into_iter()
+            .chain(fail.into_iter().map(|region| (region, false)))
+            .collect::<Vec<_>>();
+        pieces.sort_by_key(|(region, _)| region.range(category).start);
+        let nodes = pieces
+            .into_iter()
+            .map(|(region, passes)| {
+                let threshold = region.range(category).start;
+                let node = match passes {
+                    true => self.pass(rule_ref, region),
+                    false => self.fail(rule_ref, region),
+                };
+                node.map(|node| (threshold, node))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let node = nodes
+            .into_iter()
+            .reduce(|(start, below), (threshold, above)| {
+                (start, branch(category, threshold, below, above))
+            })
+            .map(|(_, node)| node)
+            .expect("empty region");
+        Ok(node)
+    }
+
+    /// Parts which meet the rule's condition and take its action.
+    fn pass(&mut self, rule_ref: RuleRef<'a>, region: Region) -> Result<Node, Error<'a>> {
+        self.rules.insert(rule_ref, true);
+        let action = self.workflows[rule_ref.workflow].rules[rule_ref.rule].action;
+        self.action(action, region)
+    }
+
+    /// Parts which do not meet the rule's condition and go on to the next rule.
+    fn fail(&mut self, rule_ref: RuleRef<'a>, region: Region) -> Result<Node, Error<'a>> {
+        let next = RuleRef {
+            rule: rule_ref.rule + 1,
+            ..rule_ref
+        };
+        self.rule(next, region)
+    }
+
+    fn action(&mut self, action: Action<'a>, region: Region) -> Result<Node, Error<'a>> {
+        match action {
+            Action::Accept => Ok(Node::Accept),
+            Action::Reject => Ok(Node::Reject),
+            Action::NextWorkflow(id) => self.workflow(id, region),
+        }
+    }
+
+    fn analysis(&self) -> Analysis<'a> {
+        let mut analysis = Analysis::default();
+        for workflow in self.workflows.values() {
+            for rule in 0..workflow.rules.len() {
+                let rule_ref = RuleRef {
+                    workflow: workflow.id,
+                    rule,
+                };
+                match self.rules.get(&rule_ref) {
+                    None if rule == 0 => analysis.unreachable_workflows.push(workflow.id),
+                    None => analysis.unreachable_rules.push(rule_ref),
+                    Some(false) => analysis.dead_rules.push(rule_ref),
+                    Some(true) => (),
+                }
+            }
+        }
+        // Rules of unreachable workflows are reported with the workflow.
+        analysis
+            .unreachable_rules
+            .retain(|rule| !analysis.unreachable_workflows.contains(&rule.workflow));
+        analysis.unreachable_workflows.sort();
+        analysis.unreachable_rules.sort();
+        analysis.dead_rules.sort();
+        analysis
+    }
+}
+
+/// Join the subtrees either side of a threshold, or either one if they match.
+fn branch(category: Category, threshold: u64, below: Node, above: Node) -> Node {
+    if below == above {
+        return below;
+    }
+    Node::Split {
+        category,
+        threshold,
+        below: Box::new(below),
+        above: Box::new(above),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::System;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    fn compile<'a>(system: &System<'a>) -> DecisionTree<'a> {
+        let domain = Region::uniform(system.categories.len(), RATING_RANGE);
+        DecisionTree::compile(&system.workflows, domain).unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let system = System::parse(EXAMPLE);
+        let tree = compile(&system);
+        assert_eq!(tree.accepted_combinations(), 167409079868000);
+
+        let accepted = system
+            .parts
+            .iter()
+            .map(|part| tree.is_accepted(part))
+            .collect::<Vec<_>>();
+        assert_eq!(accepted, vec![true, false, true, false, true]);
+
+        let regions = tree.accepted_regions();
+        for part in &system.parts {
+            let count = regions
+                .iter()
+                .filter(|region| region.contains(part))
+                .count();
+            assert_eq!(count, tree.is_accepted(part) as usize);
+        }
+    }
+
+    #[test]
+    fn normalised_tree() {
+        // lnx{m>1548:A,A} and gd{a>3333:R,R} collapse to single leaves.
+        let system = System::parse("in{s<1351:gd,lnx}\nlnx{m>1548:A,A}\ngd{a>3333:R,R}");
+        let tree = compile(&system);
+        assert_eq!(
+            *tree.root(),
+            Node::Split {
+                category: system.categories.get("s").unwrap(),
+                threshold: 1351,
+                below: Box::new(Node::Reject),
+                above: Box::new(Node::Accept),
+            }
+        );
+        assert_eq!(tree.depth(), 1);
+    }
+
+    #[test]
+    fn analysis() {
+        let system = System::parse("in{x<2000:a,x>1000:b,R}\na{x>3000:R,m<4001:A,R}\nb{A}\nc{R}");
+        let tree = compile(&system);
+        assert_eq!(
+            *tree.analysis(),
+            Analysis {
+                unreachable_workflows: vec!["c"],
+                unreachable_rules: vec![
+                    RuleRef {
+                        workflow: "a",
+                        rule: 2
+                    },
+                    RuleRef {
+                        workflow: "in",
+                        rule: 2
+                    },
+                ],
+                dead_rules: vec![RuleRef {
+                    workflow: "a",
+                    rule: 0
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn custom_domain_and_operators() {
+        let system = System::parse("in{size==3:A,size>=8:big,R}\nbig{size<=9:A,R}");
+        let tree = DecisionTree::compile(&system.workflows, Region::uniform(1, 0..11)).unwrap();
+        let size = system.categories.get("size").unwrap();
+        let ranges = tree
+            .accepted_regions()
+            .iter()
+            .map(|region| region.range(size).clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [3..4, 8..10]);
+        assert_eq!(tree.accepted_combinations(), 3);
+    }
+
+    #[test]
+    fn cycle() {
+        let system = System::parse("in{x<5:b,R}\nb{a}\na{x<5:b,R}");
+        let domain = Region::uniform(system.categories.len(), RATING_RANGE);
+        assert_eq!(
+            DecisionTree::compile(&system.workflows, domain).unwrap_err(),
+            Error::Cycle { workflow: "b" }
+        );
+
+        // The loop back to `in` only takes parts which `a` has already sent on.
+        let system = System::parse("in{x<5:a,R}\na{x<3:A,x<10:R,in}");
+        assert_eq!(compile(&system).accepted_combinations(), 2);
+    }
+}
@@ -1,6 +1,9 @@
+pub mod decision_tree;
 pub mod part1;
 pub mod part2;
 pub mod split_range;
+pub mod trace;
+pub mod workflow;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
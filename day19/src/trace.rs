@@ -0,0 +1,236 @@
+use crate::decision_tree::{Region, RuleRef};
+use crate::workflow::{Action, Categories, Condition, Error, Part, Workflows, START_WORKFLOW};
+use std::collections::HashSet;
+
+/// The workflows a part went through, with the rule that sent it on from each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace<'a> {
+    pub steps: Vec<RuleRef<'a>>,
+    pub accepted: bool,
+}
+
+impl<'a> Trace<'a> {
+    pub fn new(part: &Part, workflows: &Workflows<'a>) -> Result<Self, Error<'a>> {
+        let mut steps = vec![];
+        let mut visited = HashSet::new();
+        let mut next_workflow = START_WORKFLOW;
+        loop {
+            let workflow = workflows.get(next_workflow).ok_or(Error::Missing {
+                workflow: next_workflow,
+            })?;
+            if !visited.insert(workflow.id) {
+                return Err(Error::Cycle {
+                    workflow: workflow.id,
+                });
+            }
+            let rule = workflow.matching_rule(part);
+            steps.push(RuleRef {
+                workflow: workflow.id,
+                rule,
+            });
+            match workflow.rules[rule].action {
+                Action::Accept => {
+                    return Ok(Self {
+                        steps,
+                        accepted: true,
+                    })
+                }
+                Action::Reject => {
+                    return Ok(Self {
+                        steps,
+                        accepted: false,
+                    })
+                }
+                Action::NextWorkflow(id) => next_workflow = id,
+            }
+        }
+    }
+
+    pub fn workflows(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.steps.iter().map(|step| step.workflow)
+    }
+
+    /// The path as text, such as `in(rule 1: s<1351) -> px(rule 1: a<2006) -> qkq -> crn -> R`.
+    /// Rules are numbered from 1, and workflows left by their final fallback
+    /// rule are shown by name alone.
+    pub fn explain(&self, workflows: &Workflows, categories: &Categories) -> String {
+        let mut output = String::new();
+        for step in &self.steps {
+            output.push_str(step.workflow);
+            let condition = &workflows[step.workflow].rules[step.rule].condition;
+            if *condition != Condition::None {
+                output.push_str(&format!(
+                    "(rule {}: {})",
+                    step.rule + 1,
+                    condition.describe(categories)
+                ));
+            }
+            output.push_str(" -> ");
+        }
+        output.push(if self.accepted { 'A' } else { 'R' });
+        output
+    }
+}
+
+/// Disjoint regions of the domain holding every part which reaches the given
+/// rule. Rule 0 stands for the workflow itself.
+pub fn constraints<'a>(
+    workflows: &Workflows<'a>,
+    domain: &Region,
+    target: RuleRef<'a>,
+) -> Result<Vec<Region>, Error<'a>> {
+    let mut regions = vec![];
+    let start = RuleRef {
+        workflow: START_WORKFLOW,
+        rule: 0,
+    };
+    // Each region carries the workflows it went through to get there.
+    let mut stack = vec![(start, domain.clone(), vec![START_WORKFLOW])];
+    while let Some((rule_ref, region, path)) = stack.pop() {
+        if rule_ref == target {
+            regions.push(region);
+            continue;
+        }
+        let rule = &workflows
+            .get(rule_ref.workflow)
+            .ok_or(Error::Missing {
+                workflow: rule_ref.workflow,
+            })?
+            .rules[rule_ref.rule];
+        let (pass, fail) = region.partition(&rule.condition);
+        for region in fail {
+            let next = RuleRef {
+                rule: rule_ref.rule + 1,
+                ..rule_ref
+            };
+            stack.push((next, region, path.clone()));
+        }
+        if let (Some(region), Action::NextWorkflow(id)) = (pass, rule.action) {
+            if path.contains(&id) {
+                return Err(Error::Cycle { workflow: id });
+            }
+            let next = RuleRef {
+                workflow: id,
+                rule: 0,
+            };
+            let mut path = path;
+            path.push(id);
+            stack.push((next, region, path));
+        }
+    }
+    Ok(regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision_tree::RATING_RANGE;
+    use crate::workflow::System;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn explain() {
+        let system = System::parse(EXAMPLE);
+        let explanations = system
+            .parts
+            .iter()
+            .map(|part| {
+                Trace::new(part, &system.workflows)
+                    .unwrap()
+                    .explain(&system.workflows, &system.categories)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            explanations,
+            vec![
+                "in -> qqz(rule 1: s>2770) -> qs -> lnx(rule 1: m>1548) -> A",
+                "in(rule 1: s<1351) -> px -> rfg(rule 1: s<537) -> gd -> R",
+                "in -> qqz(rule 2: m<1801) -> hdj -> pv -> A",
+                "in(rule 1: s<1351) -> px(rule 1: a<2006) -> qkq -> crn -> R",
+                "in(rule 1: s<1351) -> px -> rfg -> A",
+            ]
+        );
+        for part in &system.parts {
+            let trace = Trace::new(part, &system.workflows).unwrap();
+            assert_eq!(Ok(trace.accepted), part.is_accepted(&system.workflows));
+        }
+    }
+
+    #[test]
+    fn reverse_query() {
+        let system = System::parse(EXAMPLE);
+        let domain = Region::uniform(system.categories.len(), RATING_RANGE);
+        let target = RuleRef {
+            workflow: "qkq",
+            rule: 1,
+        };
+        let regions = constraints(&system.workflows, &domain, target).unwrap();
+        let described = regions
+            .iter()
+            .map(|region| region.describe(&domain, &system.categories))
+            .collect::<Vec<_>>();
+        assert_eq!(described, vec!["x>=1416, a<2006, s<1351"]);
+
+        let everything = constraints(
+            &system.workflows,
+            &domain,
+            RuleRef {
+                workflow: START_WORKFLOW,
+                rule: 0,
+            },
+        )
+        .unwrap();
+        assert_eq!(everything, vec![domain.clone()]);
+        assert_eq!(everything[0].describe(&domain, &system.categories), "any");
+
+        // Every part which reaches lnx meets one of the regions.
+        let target = RuleRef {
+            workflow: "lnx",
+            rule: 0,
+        };
+        let regions = constraints(&system.workflows, &domain, target).unwrap();
+        for part in &system.parts {
+            let trace = Trace::new(part, &system.workflows).unwrap();
+            let reached = trace.workflows().any(|workflow| workflow == "lnx");
+            let count = regions.iter().filter(|r| r.contains(part)).count();
+            assert_eq!(count, reached as usize);
+        }
+    }
+
+    #[test]
+    fn cycle() {
+        let system = System::parse("in{x<5:b,R}\nb{a}\na{x<5:b,R}\n\n{x=4}");
+        assert_eq!(
+            Trace::new(&system.parts[0], &system.workflows),
+            Err(Error::Cycle { workflow: "b" })
+        );
+    }
+
+    #[test]
+    fn cyclic_constraints() {
+        let system = System::parse("in{x<5:b,R}\nb{a}\na{x<5:b,R}\n\n{x=4}");
+        let domain = Region::uniform(system.categories.len(), RATING_RANGE);
+        let target = RuleRef {
+            workflow: "a",
+            rule: 1,
+        };
+        assert_eq!(
+            constraints(&system.workflows, &domain, target),
+            Err(Error::Cycle { workflow: "b" })
+        );
+
+        // A loop back that no part can take is fine.
+        let system = System::parse("in{x<5:a,R}\na{x<3:A,x<10:R,in}\n\n{x=4}");
+        let target = RuleRef {
+            workflow: "a",
+            rule: 1,
+        };
+        let regions = constraints(&system.workflows, &domain, target).unwrap();
+        let described = regions
+            .iter()
+            .map(|region| region.describe(&domain, &system.categories))
+            .collect::<Vec<_>>();
+        assert_eq!(described, vec!["3<=x<5"]);
+    }
+}
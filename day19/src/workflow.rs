@@ -0,0 +1,410 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Name of the workflow every part starts in.
+pub const START_WORKFLOW: &str = "in";
+
+/// A rating category, by its position in [`Categories`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Category(pub usize);
+
+/// Names of the rating categories, in the order they first appear in the input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Categories<'a> {
+    names: Vec<&'a str>,
+}
+
+impl<'a> Categories<'a> {
+    pub fn new(names: &[&'a str]) -> Self {
+        let mut categories = Self::default();
+        for name in names {
+            categories.register(name);
+        }
+        categories
+    }
+
+    /// Look up a category, adding it if it has not been seen before.
+    pub fn register(&mut self, name: &'a str) -> Category {
+        self.get(name).unwrap_or_else(|| {
+            self.names.push(name);
+            Category(self.names.len() - 1)
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Category> {
+        self.names.iter().position(|n| *n == name).map(Category)
+    }
+
+    pub fn name(&self, category: Category) -> &'a str {
+        self.names[category.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Category> {
+        (0..self.names.len()).map(Category)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Less,
+    Greater,
+    LessOrEqual,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Operator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Operator::Less => "<",
+            Operator::Greater => ">",
+            Operator::LessOrEqual => "<=",
+            Operator::GreaterOrEqual => ">=",
+            Operator::Equal => "==",
+        }
+    }
+
+    pub fn compare(self, lhs: u64, rhs: u64) -> bool {
+        match self {
+            Operator::Less => lhs < rhs,
+            Operator::Greater => lhs > rhs,
+            Operator::LessOrEqual => lhs <= rhs,
+            Operator::GreaterOrEqual => lhs >= rhs,
+            Operator::Equal => lhs == rhs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Condition {
+    None,
+    Compare {
+        category: Category,
+        operator: Operator,
+        value: u64,
+    },
+}
+
+impl Condition {
+    fn parse<'a>(condition: &'a str, categories: &mut Categories<'a>) -> Self {
+        let split = condition
+            .find(['<', '>', '='])
+            .unwrap_or_else(|| panic!("unknown condition: {}", condition));
+        let (label, rest) = condition.split_at(split);
+        let operator = [
+            Operator::LessOrEqual,
+            Operator::GreaterOrEqual,
+            Operator::Equal,
+            Operator::Less,
+            Operator::Greater,
+        ]
+        .into_iter()
+        .find(|operator| rest.starts_with(operator.symbol()))
+        .unwrap_or_else(|| panic!("unknown condition: {}", condition));
+        let value = rest[operator.symbol().len()..]
+            .parse()
+            .expect("condition value could not be read");
+        Condition::Compare {
+            category: categories.register(label),
+            operator,
+            value,
+        }
+    }
+
+    pub fn matches(&self, part: &Part) -> bool {
+        match *self {
+            Condition::None => true,
+            Condition::Compare {
+                category,
+                operator,
+                value,
+            } => operator.compare(part.rating(category), value),
+        }
+    }
+
+    /// The condition as written in the input, such as `a<2006`.
+    pub fn describe(&self, categories: &Categories) -> String {
+        match *self {
+            Condition::None => String::from("true"),
+            Condition::Compare {
+                category,
+                operator,
+                value,
+            } => format!(
+                "{}{}{}",
+                categories.name(category),
+                operator.symbol(),
+                value
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action<'a> {
+    Accept,
+    Reject,
+    NextWorkflow(&'a str),
+}
+
+impl<'a> Action<'a> {
+    fn parse(action: &'a str) -> Self {
+        match action {
+            "A" => Self::Accept,
+            "R" => Self::Reject,
+            _ => Self::NextWorkflow(action),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule<'a> {
+    pub condition: Condition,
+    pub action: Action<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workflow<'a> {
+    pub id: &'a str,
+    pub rules: Vec<Rule<'a>>,
+}
+
+impl<'a> Workflow<'a> {
+    pub fn parse(line: &'a str, categories: &mut Categories<'a>) -> Self {
+        let (id, rules) = line.trim_end_matches('}').split_once('{').unwrap();
+        let rules = rules
+            .split(',')
+            .map(|rule| {
+                if let Some((condition, action)) = rule.split_once(':') {
+                    Rule {
+                        condition: Condition::parse(condition, categories),
+                        action: Action::parse(action),
+                    }
+                } else {
+                    Rule {
+                        condition: Condition::None,
+                        action: Action::parse(rule),
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        Self { id, rules }
+    }
+
+    /// Position of the first rule whose condition the part meets.
+    pub fn matching_rule(&self, part: &Part) -> usize {
+        self.rules
+            .iter()
+            .position(|rule| rule.condition.matches(part))
+            .unwrap_or_else(|| panic!("no rule in {} matches {:?}", self.id, part))
+    }
+}
+
+pub type Workflows<'a> = HashMap<&'a str, Workflow<'a>>;
+
+/// Why parts can't be sent through the workflows to be accepted or rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<'a> {
+    /// Parts sent back to a workflow they already went through, which would
+    /// loop forever.
+    Cycle { workflow: &'a str },
+    /// Parts sent to a workflow that doesn't exist.
+    Missing { workflow: &'a str },
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Cycle { workflow } => write!(f, "workflow cycle through {}", workflow),
+            Error::Missing { workflow } => write!(f, "workflow not found: {}", workflow),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Part {
+    /// Ratings indexed by [`Category`].
+    pub ratings: Vec<u64>,
+}
+
+impl Part {
+    /// Parse a part, which must have a rating for every category.
+    pub fn parse(line: &str, categories: &Categories) -> Self {
+        let mut ratings = vec![None; categories.len()];
+        for category in line
+            .trim_start_matches('{')
+            .trim_end_matches('}')
+            .split(',')
+        {
+            let (label, value) = category.split_once('=').unwrap();
+            let category = categories
+                .get(label)
+                .unwrap_or_else(|| panic!("unknown category: {}", label));
+            ratings[category.0] = Some(value.parse().unwrap());
+        }
+        let ratings = ratings
+            .into_iter()
+            .enumerate()
+            .map(|(n, rating)| {
+                rating.unwrap_or_else(|| {
+                    panic!(
+                        "part {} has no {} rating",
+                        line,
+                        categories.name(Category(n))
+                    )
+                })
+            })
+            .collect();
+        Self { ratings }
+    }
+
+    pub fn rating(&self, category: Category) -> u64 {
+        self.ratings[category.0]
+    }
+
+    pub fn ratings_total(&self) -> u64 {
+        self.ratings.iter().sum()
+    }
+
+    pub fn is_accepted<'a>(&self, workflows: &Workflows<'a>) -> Result<bool, Error<'a>> {
+        let mut visited = HashSet::new();
+        let mut next_workflow = START_WORKFLOW;
+        loop {
+            let workflow = workflows.get(next_workflow).ok_or(Error::Missing {
+                workflow: next_workflow,
+            })?;
+            if !visited.insert(workflow.id) {
+                return Err(Error::Cycle {
+                    workflow: workflow.id,
+                });
+            }
+            match workflow.rules[workflow.matching_rule(self)].action {
+                Action::Accept => return Ok(true),
+                Action::Reject => return Ok(false),
+                Action::NextWorkflow(id) => next_workflow = id,
+            }
+        }
+    }
+}
+
+/// Workflows and parts from the puzzle input, with the categories they use.
+#[derive(Debug, Clone)]
+pub struct System<'a> {
+    pub categories: Categories<'a>,
+    pub workflows: Workflows<'a>,
+    pub parts: Vec<Part>,
+}
+
+impl<'a> System<'a> {
+    /// Parse the input. Categories are declared by the ratings of the parts
+    /// and by the workflow conditions.
+    pub fn parse(input: &'a str) -> Self {
+        let (workflows, parts) = input.split_once("\n\n").unwrap_or((input, ""));
+        let mut categories = Categories::default();
+        for line in parts.lines() {
+            for category in line
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .split(',')
+            {
+                categories.register(category.split_once('=').unwrap().0);
+            }
+        }
+        let workflows = workflows
+            .lines()
+            .map(|line| Workflow::parse(line, &mut categories))
+            .map(|workflow| (workflow.id, workflow))
+            .collect();
+        let parts = parts
+            .lines()
+            .map(|line| Part::parse(line, &categories))
+            .collect();
+        Self {
+            categories,
+            workflows,
+            parts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_categories() {
+        let system = System::parse(
+            "in{weight>=10:heavy,colour==3:A,R}\nheavy{weight<=20:A,R}\n\n{weight=10,colour=1}\n{weight=21,colour=1}\n{weight=5,colour=3}\n{weight=5,colour=4}",
+        );
+        assert_eq!(system.categories, Categories::new(&["weight", "colour"]));
+        let accepted = system
+            .parts
+            .iter()
+            .map(|part| part.is_accepted(&system.workflows).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(accepted, vec![true, false, true, false]);
+    }
+
+    #[test]
+    fn operators() {
+        let mut categories = Categories::default();
+        let condition = Condition::parse("abc<=7", &mut categories);
+        assert_eq!(
+            condition,
+            Condition::Compare {
+                category: Category(0),
+                operator: Operator::LessOrEqual,
+                value: 7
+            }
+        );
+        assert_eq!(categories.name(Category(0)), "abc");
+        for (symbol, expected) in [
+            ("<", [true, false, false]),
+            (">", [false, false, true]),
+            ("<=", [true, true, false]),
+            (">=", [false, true, true]),
+            ("==", [false, true, false]),
+        ] {
+            let text = format!("abc{symbol}7");
+            let condition = Condition::parse(&text, &mut Categories::default());
+            let results = [6, 7, 8].map(|rating| {
+                condition.matches(&Part {
+                    ratings: vec![rating],
+                })
+            });
+            assert_eq!(results, expected, "abc{symbol}7");
+        }
+    }
+
+    #[test]
+    fn cycle() {
+        let system = System::parse("in{x<5:a,R}\na{in}\n\n{x=4}\n{x=5}");
+        let [looping, rejected] = [0, 1].map(|n| system.parts[n].is_accepted(&system.workflows));
+        assert_eq!(looping, Err(Error::Cycle { workflow: "in" }));
+        assert_eq!(rejected, Ok(false));
+        assert_eq!(
+            Error::Cycle { workflow: "in" }.to_string(),
+            "workflow cycle through in"
+        );
+    }
+
+    #[test]
+    fn missing() {
+        let system = System::parse("in{x<5:a,b}\na{A}\n\n{x=4}\n{x=5}");
+        let [accepted, missing] = [0, 1].map(|n| system.parts[n].is_accepted(&system.workflows));
+        assert_eq!(accepted, Ok(true));
+        assert_eq!(missing, Err(Error::Missing { workflow: "b" }));
+        assert_eq!(
+            Error::Missing { workflow: "b" }.to_string(),
+            "workflow not found: b"
+        );
+    }
+}
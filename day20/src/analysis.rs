@@ -0,0 +1,301 @@
+use crate::network::{ModuleType, Network, BROADCASTER};
+use num::Integer;
+use std::fmt;
+
+/// A chain of flip-flops started by the broadcaster which counts button
+/// presses in binary, with a conjunction that sends a low pulse and resets the
+/// count every `period` presses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<'a> {
+    /// Flip-flops from the lowest bit up.
+    pub bits: Vec<&'a str>,
+    pub conjunction: &'a str,
+    /// The one module outside the counter that the conjunction sends to.
+    pub output: &'a str,
+    pub period: usize,
+}
+
+impl<'a> Counter<'a> {
+    /// Read the counter starting at flip-flop `first` from the wiring.
+    pub fn read(network: &Network<'a>, first: &'a str) -> Result<Self, Failure<'a>> {
+        let not_counter = |module, reason| Err(Failure::NotCounter { module, reason });
+        let mut bits = vec![];
+        let mut conjunction = None;
+        let mut next = Some(first);
+        while let Some(module) = next {
+            if network.module_type(module) != ModuleType::FlipFlop {
+                return not_counter(
+                    module,
+                    "chain continues to a module which is not a flip-flop",
+                );
+            }
+            bits.push(module);
+            next = None;
+            for destination in network.destinations(module) {
+                if network.module_type(destination) == ModuleType::FlipFlop {
+                    if next.replace(destination).is_some() {
+                        return not_counter(module, "flip-flop drives more than one flip-flop");
+                    }
+                } else if *conjunction.get_or_insert(destination) != destination {
+                    return not_counter(module, "chain drives more than one other module");
+                }
+            }
+            if next.is_some_and(|next| bits.contains(&next)) {
+                return not_counter(module, "flip-flops form a loop");
+            }
+        }
+        let Some(conjunction) = conjunction else {
+            return not_counter(first, "chain drives no conjunction");
+        };
+        if network.module_type(conjunction) != ModuleType::Conjunction {
+            return not_counter(
+                conjunction,
+                "chain drives a module which is not a conjunction",
+            );
+        }
+
+        let sources = network.sources(conjunction);
+        let is_set = |bit: &str| sources.contains(&bit);
+        if !is_set(bits[0]) || !is_set(bits[bits.len() - 1]) {
+            return not_counter(
+                conjunction,
+                "lowest and highest bits must both feed the conjunction",
+            );
+        }
+        if sources.len() != bits.iter().filter(|bit| is_set(bit)).count() {
+            return not_counter(conjunction, "conjunction has inputs from outside the chain");
+        }
+        let mut resets = network.destinations(conjunction);
+        let outputs = resets
+            .iter()
+            .filter(|module| !bits.contains(module))
+            .copied()
+            .collect::<Vec<_>>();
+        let [output] = outputs[..] else {
+            return not_counter(conjunction, "conjunction must have exactly one output");
+        };
+        resets.retain(|module| *module != output);
+        resets.sort_unstable();
+        let mut expected = bits
+            .iter()
+            .enumerate()
+            .filter(|(n, bit)| *n == 0 || !is_set(bit))
+            .map(|(_, bit)| *bit)
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+        if resets != expected {
+            return not_counter(
+                conjunction,
+                "conjunction must reset the lowest bit and every unset bit",
+            );
+        }
+        for (n, bit) in bits.iter().enumerate() {
+            let allowed = [if n == 0 { BROADCASTER } else { bits[n - 1] }, conjunction];
+            if network
+                .sources(bit)
+                .iter()
+                .any(|source| !allowed.contains(source))
+            {
+                return not_counter(bit, "flip-flop has inputs from outside the counter");
+            }
+        }
+
+        let period = bits
+            .iter()
+            .enumerate()
+            .filter(|(_, bit)| is_set(bit))
+            .map(|(n, _)| 1 << n)
+            .sum();
+        Ok(Self {
+            bits,
+            conjunction,
+            output,
+            period,
+        })
+    }
+}
+
+/// The assumption about the shape of the network that did not hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure<'a> {
+    UnknownModule(&'a str),
+    /// The target is not fed by exactly one conjunction.
+    NotFedByConjunction(&'a str),
+    /// A module feeding the target's conjunction is not a conjunction with a
+    /// single input and a single output.
+    NotInverter(&'a str),
+    /// An inverter is not fed by the conjunction of a counter.
+    NotCounterOutput(&'a str),
+    NotCounter {
+        module: &'a str,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for Failure<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::UnknownModule(module) => write!(f, "unknown module {}", module),
+            Failure::NotFedByConjunction(module) => {
+                write!(f, "{} is not fed by a single conjunction", module)
+            }
+            Failure::NotInverter(module) => write!(f, "{} is not an inverter", module),
+            Failure::NotCounterOutput(module) => {
+                write!(f, "{} is not fed by the conjunction of a counter", module)
+            }
+            Failure::NotCounter { module, reason } => {
+                write!(f, "{} is not part of a binary counter: {}", module, reason)
+            }
+        }
+    }
+}
+
+/// Read every counter started by the broadcaster.
+pub fn counters<'a>(network: &Network<'a>) -> Result<Vec<Counter<'a>>, Failure<'a>> {
+    if !network.contains(BROADCASTER) {
+        return Err(Failure::UnknownModule(BROADCASTER));
+    }
+    network
+        .destinations(BROADCASTER)
+        .into_iter()
+        .map(|first| Counter::read(network, first))
+        .collect()
+}
+
+/// Split the network into the independent counters which together send a low
+/// pulse to `target`. This holds when the target is fed by one conjunction,
+/// whose inputs are each inverted outputs of a counter.
+pub fn analyse<'a>(
+    network: &Network<'a>,
+    target: &'a str,
+) -> Result<Vec<Counter<'a>>, Failure<'a>> {
+    if !network.contains(target) {
+        return Err(Failure::UnknownModule(target));
+    }
+    let hub = match network.sources(target)[..] {
+        [hub] if network.module_type(hub) == ModuleType::Conjunction => hub,
+        _ => return Err(Failure::NotFedByConjunction(target)),
+    };
+    let mut counters = counters(network)?;
+    network
+        .sources(hub)
+        .into_iter()
+        .map(|inverter| {
+            let source = match network.sources(inverter)[..] {
+                [source]
+                    if network.module_type(inverter) == ModuleType::Conjunction
+                        && network.destinations(inverter) == [hub] =>
+                {
+                    source
+                }
+                _ => return Err(Failure::NotInverter(inverter)),
+            };
+            let position = counters
+                .iter()
+                .position(|counter| counter.conjunction == source && counter.output == inverter)
+                .ok_or(Failure::NotCounterOutput(inverter))?;
+            Ok(counters.swap_remove(position))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Method<'a> {
+    /// Worked out from the periods of the counters.
+    Counters(Vec<Counter<'a>>),
+    /// Found by pressing the button, because the analysis failed.
+    Simulation(Failure<'a>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report<'a> {
+    /// Fewest button presses for a low pulse to reach the target, if found.
+    pub presses: Option<usize>,
+    pub method: Method<'a>,
+}
+
+/// Find the fewest button presses before `target` gets a low pulse, from the
+/// counters if possible and otherwise by simulating up to `limit` presses.
+pub fn presses_until_low<'a>(network: &Network<'a>, target: &'a str, limit: usize) -> Report<'a> {
+    match analyse(network, target) {
+        Ok(counters) => Report {
+            presses: counters
+                .iter()
+                .map(|counter| counter.period)
+                .reduce(|a, b| a.lcm(&b)),
+            method: Method::Counters(counters),
+        },
+        Err(failure) => {
+            let mut network = network.clone();
+            network.reset();
+            let presses = (1..=limit).find(|_| {
+                let mut found = false;
+                network.press(|pulse| found |= pulse.destination == target && !pulse.is_high);
+                found
+            });
+            Report {
+                presses,
+                method: Method::Simulation(failure),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE2: &str = include_str!("../example2.txt");
+    const EXAMPLE3: &str = include_str!("../example3.txt");
+
+    #[test]
+    fn counters_from_wiring() {
+        let network = Network::parse(EXAMPLE3);
+        let report = presses_until_low(&network, "rx", 0);
+        assert_eq!(report.presses, Some(15));
+        let Method::Counters(counters) = report.method else {
+            panic!("analysis failed: {:?}", report.method);
+        };
+        assert_eq!(
+            counters[1],
+            Counter {
+                bits: vec!["b1", "b2", "b3"],
+                conjunction: "cb",
+                output: "ib",
+                period: 5,
+            }
+        );
+        assert_eq!(counters[0].period, 3);
+    }
+
+    #[test]
+    fn simulation_fallback() {
+        let network = Network::parse(EXAMPLE2);
+        let report = presses_until_low(&network, "output", 10);
+        assert_eq!(report.presses, Some(1));
+        assert_eq!(
+            report.method,
+            Method::Simulation(Failure::NotCounter {
+                module: "a",
+                reason: "chain drives more than one other module"
+            })
+        );
+        assert_eq!(analyse(&network, "rx"), Err(Failure::UnknownModule("rx")));
+    }
+
+    #[test]
+    fn failed_assumptions() {
+        // ca also resets a2, so it no longer counts to 3.
+        let input = EXAMPLE3.replace("&ca -> ia, a1", "&ca -> ia, a1, a2");
+        let network = Network::parse(&input);
+        let report = presses_until_low(&network, "rx", 1000);
+        let Method::Simulation(failure) = report.method else {
+            panic!("analysis should fail");
+        };
+        assert_eq!(
+            failure.to_string(),
+            "ca is not part of a binary counter: conjunction must reset the lowest bit and every unset bit"
+        );
+        assert_eq!(report.presses, Some(5));
+    }
+}
@@ -1,5 +1,8 @@
+pub mod analysis;
+pub mod network;
 pub mod part1;
 pub mod part2;
+pub mod render;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
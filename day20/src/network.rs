@@ -0,0 +1,364 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/// Name of the module the button sends its pulse to.
+pub const BROADCASTER: &str = "broadcaster";
+/// Name given as the source of the pulse sent by the button.
+pub const BUTTON: &str = "button";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModuleType {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    /// A module only named as a destination, such as `output` or `rx`.
+    Untyped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Module<'a> {
+    name: &'a str,
+    module_type: ModuleType,
+    /// Destination modules, with the input of each this module is connected to.
+    destinations: Vec<(usize, usize)>,
+    sources: Vec<usize>,
+    /// Position of the module's state in [`State::bits`].
+    offset: usize,
+}
+
+/// A pulse sent from one module to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pulse<'a> {
+    pub source: &'a str,
+    pub destination: &'a str,
+    pub is_high: bool,
+    /// The button press which started the pulse, counting from 1.
+    pub press: usize,
+    /// Position of the pulse among all those sent by the press.
+    pub step: usize,
+}
+
+/// Memory of every flip-flop and conjunction in the network.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct State {
+    /// One bit per flip-flop, and one per input of each conjunction.
+    pub bits: Vec<bool>,
+}
+
+/// Simulator for a network of modules.
+#[derive(Debug, Clone)]
+pub struct Network<'a> {
+    modules: Vec<Module<'a>>,
+    index: HashMap<&'a str, usize>,
+    state: State,
+    presses: usize,
+}
+
+impl<'a> Network<'a> {
+    pub fn parse(input: &'a str) -> Self {
+        let mut network = Self {
+            modules: vec![],
+            index: HashMap::new(),
+            state: State { bits: vec![] },
+            presses: 0,
+        };
+        let lines = input
+            .lines()
+            .map(|line| {
+                let (name, destinations) = line.split_once(" -> ").unwrap();
+                let (name, module_type) = match name.chars().next() {
+                    Some('%') => (&name[1..], ModuleType::FlipFlop),
+                    Some('&') => (&name[1..], ModuleType::Conjunction),
+                    _ if name == BROADCASTER => (name, ModuleType::Broadcaster),
+                    _ => panic!("Unknown module type: {}", line),
+                };
+                let id = network.module(name);
+                network.modules[id].module_type = module_type;
+                (id, destinations)
+            })
+            .collect::<Vec<_>>();
+        for (id, destinations) in lines {
+            for destination in destinations.split(", ") {
+                let destination = network.module(destination);
+                let input = network.modules[destination].sources.len();
+                network.modules[destination].sources.push(id);
+                network.modules[id].destinations.push((destination, input));
+            }
+        }
+        let mut offset = 0;
+        for module in &mut network.modules {
+            module.offset = offset;
+            offset += match module.module_type {
+                ModuleType::FlipFlop => 1,
+                ModuleType::Conjunction => module.sources.len(),
+                ModuleType::Broadcaster | ModuleType::Untyped => 0,
+            };
+        }
+        network.state.bits = vec![false; offset];
+        network
+    }
+
+    /// Look up a module, adding it as untyped if it has not been seen before.
+    fn module(&mut self, name: &'a str) -> usize {
+        *self.index.entry(name).or_insert_with(|| {
+            self.modules.push(Module {
+                name,
+                module_type: ModuleType::Untyped,
+                destinations: vec![],
+                sources: vec![],
+                offset: 0,
+            });
+            self.modules.len() - 1
+        })
+    }
+
+    fn get(&self, name: &str) -> &Module<'a> {
+        let id = self
+            .index
+            .get(name)
+            .unwrap_or_else(|| panic!("Unknown module: {}", name));
+        &self.modules[*id]
+    }
+
+    /// Names of every module, including untyped ones, in order of first appearance.
+    pub fn names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.modules.iter().map(|module| module.name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+
+    pub fn module_type(&self, name: &str) -> ModuleType {
+        self.get(name).module_type
+    }
+
+    pub fn destinations(&self, name: &str) -> Vec<&'a str> {
+        self.get(name)
+            .destinations
+            .iter()
+            .map(|(id, _)| self.modules[*id].name)
+            .collect()
+    }
+
+    pub fn sources(&self, name: &str) -> Vec<&'a str> {
+        self.get(name)
+            .sources
+            .iter()
+            .map(|id| self.modules[*id].name)
+            .collect()
+    }
+
+    /// Whether a flip-flop is on.
+    pub fn is_on(&self, name: &str) -> bool {
+        let module = self.get(name);
+        assert_eq!(
+            module.module_type,
+            ModuleType::FlipFlop,
+            "{} is not a flip-flop",
+            name
+        );
+        self.state.bits[module.offset]
+    }
+
+    /// The most recent pulse a conjunction got from each of its sources.
+    pub fn memory(&self, name: &str) -> Vec<(&'a str, bool)> {
+        let module = self.get(name);
+        assert_eq!(
+            module.module_type,
+            ModuleType::Conjunction,
+            "{} is not a conjunction",
+            name
+        );
+        module
+            .sources
+            .iter()
+            .enumerate()
+            .map(|(n, id)| (self.modules[*id].name, self.state.bits[module.offset + n]))
+            .collect()
+    }
+
+    /// Number of times the button has been pressed.
+    pub fn presses(&self) -> usize {
+        self.presses
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Take a copy of the state, to [`restore`](Self::restore) later.
+    pub fn snapshot(&self) -> State {
+        self.state.clone()
+    }
+
+    pub fn restore(&mut self, state: State) {
+        assert_eq!(
+            state.bits.len(),
+            self.state.bits.len(),
+            "state is for another network"
+        );
+        self.state = state;
+    }
+
+    /// Set every module back to its initial state.
+    pub fn reset(&mut self) {
+        self.state.bits.fill(false);
+        self.presses = 0;
+    }
+
+    /// Hash of the state, for detecting cycles.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.state.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Press the button once, passing every pulse sent to `on_pulse` in the
+    /// order they are processed.
+    pub fn press<F>(&mut self, mut on_pulse: F)
+    where
+        F: FnMut(&Pulse<'a>),
+    {
+        self.presses += 1;
+        let broadcaster = *self
+            .index
+            .get(BROADCASTER)
+            .unwrap_or_else(|| panic!("Unknown module: {}", BROADCASTER));
+        let mut queue = VecDeque::from([(None, broadcaster, 0, false)]);
+        let mut step = 0;
+        while let Some((source, destination, input, is_high)) = queue.pop_front() {
+            let module = &self.modules[destination];
+            on_pulse(&Pulse {
+                source: source.map_or(BUTTON, |id: usize| self.modules[id].name),
+                destination: module.name,
+                is_high,
+                press: self.presses,
+                step,
+            });
+            step += 1;
+
+            let bits = &mut self.state.bits;
+            let next = match module.module_type {
+                ModuleType::Broadcaster => Some(is_high),
+                ModuleType::FlipFlop if is_high => None,
+                ModuleType::FlipFlop => {
+                    bits[module.offset] = !bits[module.offset];
+                    Some(bits[module.offset])
+                }
+                ModuleType::Conjunction => {
+                    bits[module.offset + input] = is_high;
+                    let memory = &bits[module.offset..module.offset + module.sources.len()];
+                    Some(!memory.iter().all(|bit| *bit))
+                }
+                ModuleType::Untyped => None,
+            };
+            if let Some(is_high) = next {
+                queue.extend(
+                    module
+                        .destinations
+                        .iter()
+                        .map(|(id, input)| (Some(destination), *id, *input, is_high)),
+                );
+            }
+        }
+    }
+
+    /// Press the button `n` times, passing every pulse to `on_pulse`.
+    pub fn press_n<F>(&mut self, n: usize, mut on_pulse: F)
+    where
+        F: FnMut(&Pulse<'a>),
+    {
+        for _ in 0..n {
+            self.press(&mut on_pulse);
+        }
+    }
+
+    /// Press the button once and collect the pulses sent.
+    pub fn pulses(&mut self) -> Vec<Pulse<'a>> {
+        let mut pulses = vec![];
+        self.press(|pulse| pulses.push(*pulse));
+        pulses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+    const EXAMPLE2: &str = include_str!("../example2.txt");
+
+    #[test]
+    fn event_log() {
+        let mut network = Network::parse(EXAMPLE2);
+        let log = network
+            .pulses()
+            .iter()
+            .map(|pulse| {
+                let level = if pulse.is_high { "high" } else { "low" };
+                format!("{} -{}-> {}", pulse.source, level, pulse.destination)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            log,
+            vec![
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -high-> inv",
+                "a -high-> con",
+                "inv -low-> b",
+                "con -high-> output",
+                "b -high-> con",
+                "con -low-> output",
+            ]
+        );
+        assert!(network.is_on("a"));
+        assert_eq!(network.memory("con"), vec![("a", true), ("b", true)]);
+        assert_eq!(network.module_type("output"), ModuleType::Untyped);
+        assert_eq!(network.sources("con"), vec!["a", "b"]);
+
+        let pulses = network.pulses();
+        assert!(pulses.iter().all(|pulse| pulse.press == 2));
+        assert_eq!(pulses.last().unwrap().step, pulses.len() - 1);
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut network = Network::parse(EXAMPLE2);
+        let initial = network.snapshot();
+        let initial_hash = network.state_hash();
+        network.press_n(3, |_| ());
+        let third = network.snapshot();
+        assert_ne!(third, initial);
+
+        let fourth = network.pulses();
+        network.restore(third);
+        assert_eq!(
+            network.pulses(),
+            fourth
+                .iter()
+                .map(|p| Pulse { press: 5, ..*p })
+                .collect::<Vec<_>>()
+        );
+
+        // The second example returns to its initial state every 4 presses.
+        network.reset();
+        network.press_n(4, |_| ());
+        assert_eq!(*network.state(), initial);
+        assert_eq!(network.state_hash(), initial_hash);
+    }
+
+    #[test]
+    fn counts() {
+        let mut network = Network::parse(EXAMPLE);
+        let (mut low, mut high) = (0, 0);
+        network.press_n(1000, |pulse| match pulse.is_high {
+            true => high += 1,
+            false => low += 1,
+        });
+        assert_eq!((low, high), (8000, 4000));
+        assert_eq!(network.presses(), 1000);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::analysis::Counter;
+use crate::network::{ModuleType, Network};
+use std::fmt::Write;
+
+/// Fill colour of each module type, shared by both formats.
+fn colour(module_type: ModuleType) -> &'static str {
+    match module_type {
+        ModuleType::Broadcaster => "#8dd3c7",
+        ModuleType::FlipFlop => "#ffffb3",
+        ModuleType::Conjunction => "#fb8072",
+        ModuleType::Untyped => "#d9d9d9",
+    }
+}
+
+/// The module's name with the prefix used in the input.
+fn label(network: &Network, name: &str) -> String {
+    match network.module_type(name) {
+        ModuleType::FlipFlop => format!("%{}", name),
+        ModuleType::Conjunction => format!("&{}", name),
+        ModuleType::Broadcaster | ModuleType::Untyped => name.to_string(),
+    }
+}
+
+fn cluster_label(counter: &Counter) -> String {
+    format!(
+        "counter {} (period {})",
+        counter.conjunction, counter.period
+    )
+}
+
+/// Modules drawn outside every cluster.
+fn unclustered<'a>(network: &Network<'a>, counters: &[Counter]) -> Vec<&'a str> {
+    network
+        .names()
+        .filter(|name| {
+            !counters
+                .iter()
+                .any(|counter| counter.conjunction == *name || counter.bits.contains(name))
+        })
+        .collect()
+}
+
+/// The network in Graphviz DOT format, with each counter in its own cluster.
+/// Pass no counters to draw the modules without clusters.
+pub fn render_dot(network: &Network, counters: &[Counter]) -> String {
+    let node = |output: &mut String, indent: &str, name: &str| {
+        let module_type = network.module_type(name);
+        let shape = match module_type {
+            ModuleType::Broadcaster => "house",
+            ModuleType::FlipFlop => "box",
+            ModuleType::Conjunction => "invtrapezium",
+            ModuleType::Untyped => "doublecircle",
+        };
+        writeln!(
+            output,
+            r#"{}"{}" [label="{}", shape={}, fillcolor="{}"];"#,
+            indent,
+            name,
+            label(network, name),
+            shape,
+            colour(module_type)
+        )
+        .unwrap();
+    };
+
+    let mut output = String::from("digraph network {\n");
+    output.push_str("  node [style=filled];\n");
+    for counter in counters {
+        writeln!(output, r#"  subgraph "cluster_{}" {{"#, counter.conjunction).unwrap();
+        writeln!(output, r#"    label="{}";"#, cluster_label(counter)).unwrap();
+        for name in counter.bits.iter().chain([&counter.conjunction]) {
+            node(&mut output, "    ", name);
+        }
+        output.push_str("  }\n");
+    }
+    for name in unclustered(network, counters) {
+        node(&mut output, "  ", name);
+    }
+    for name in network.names() {
+        for destination in network.destinations(name) {
+            writeln!(output, r#"  "{}" -> "{}";"#, name, destination).unwrap();
+        }
+    }
+    output.push_str("}\n");
+    output
+}
+
+/// The network as a Mermaid flowchart, with each counter in its own subgraph.
+/// Pass no counters to draw the modules without subgraphs.
+pub fn render_mermaid(network: &Network, counters: &[Counter]) -> String {
+    let node = |output: &mut String, indent: &str, name: &str| {
+        let (open, close, class) = match network.module_type(name) {
+            ModuleType::Broadcaster => ("([", "])", "broadcaster"),
+            ModuleType::FlipFlop => ("[", "]", "flipflop"),
+            ModuleType::Conjunction => ("{{", "}}", "conjunction"),
+            ModuleType::Untyped => ("((", "))", "untyped"),
+        };
+        writeln!(
+            output,
+            r#"{}{}{}"{}"{}:::{}"#,
+            indent,
+            name,
+            open,
+            label(network, name),
+            close,
+            class
+        )
+        .unwrap();
+    };
+
+    let mut output = String::from("flowchart LR\n");
+    for (class, module_type) in [
+        ("broadcaster", ModuleType::Broadcaster),
+        ("flipflop", ModuleType::FlipFlop),
+        ("conjunction", ModuleType::Conjunction),
+        ("untyped", ModuleType::Untyped),
+    ] {
+        writeln!(output, "  classDef {} fill:{}", class, colour(module_type)).unwrap();
+    }
+    for counter in counters {
+        writeln!(
+            output,
+            r#"  subgraph cluster_{}["{}"]"#,
+            counter.conjunction,
+            cluster_label(counter)
+        )
+        .unwrap();
+        for name in counter.bits.iter().chain([&counter.conjunction]) {
+            node(&mut output, "    ", name);
+        }
+        output.push_str("  end\n");
+    }
+    for name in unclustered(network, counters) {
+        node(&mut output, "  ", name);
+    }
+    for name in network.names() {
+        for destination in network.destinations(name) {
+            writeln!(output, "  {} --> {}", name, destination).unwrap();
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::counters;
+
+    const EXAMPLE2: &str = include_str!("../example2.txt");
+    const EXAMPLE3: &str = include_str!("../example3.txt");
+
+    #[test]
+    fn dot() {
+        let network = Network::parse(EXAMPLE2);
+        let dot = render_dot(&network, &[]);
+        assert!(dot.starts_with("digraph network {\n"));
+        assert!(dot.contains(r##""a" [label="%a", shape=box, fillcolor="#ffffb3"];"##));
+        assert!(dot.contains(r#""con" [label="&con", shape=invtrapezium"#));
+        assert!(dot.contains(r#""output" [label="output", shape=doublecircle"#));
+        assert_eq!(dot.matches(" -> ").count(), 6);
+        assert!(!dot.contains("subgraph"));
+    }
+
+    #[test]
+    fn mermaid() {
+        let network = Network::parse(EXAMPLE2);
+        let mermaid = render_mermaid(&network, &[]);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains(r#"  broadcaster(["broadcaster"]):::broadcaster"#));
+        assert!(mermaid.contains(r#"  inv{{"&inv"}}:::conjunction"#));
+        assert!(mermaid.contains("  a --> con\n"));
+        assert_eq!(mermaid.matches(" --> ").count(), 6);
+    }
+
+    #[test]
+    fn clusters() {
+        let network = Network::parse(EXAMPLE3);
+        let counters = counters(&network).unwrap();
+        let dot = render_dot(&network, &counters);
+        assert_eq!(dot.matches("subgraph").count(), 2);
+        assert!(dot.contains(r#"label="counter cb (period 5)";"#));
+        // Every module is drawn exactly once.
+        assert_eq!(dot.matches("[label=").count(), network.names().count());
+
+        let mermaid = render_mermaid(&network, &counters);
+        assert!(mermaid.contains(r#"  subgraph cluster_ca["counter ca (period 3)"]"#));
+        assert_eq!(mermaid.matches("\n  end\n").count(), 2);
+        assert_eq!(mermaid.matches(":::").count(), network.names().count());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::garden::{Garden, UNREACHABLE};
+use std::fmt::Write;
+
+/// Whether a plot is an even or odd number of steps from the start. The elf
+/// can only end on plots with the same parity as the number of steps taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    pub fn of(n: usize) -> Self {
+        if n.is_multiple_of(2) {
+            Parity::Even
+        } else {
+            Parity::Odd
+        }
+    }
+}
+
+/// Fewest steps from the start to every plot of a rectangle of garden tiles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceField {
+    rows: usize,
+    cols: usize,
+    start: (usize, usize),
+    rocks: Vec<bool>,
+    distances: Vec<u32>,
+}
+
+impl DistanceField {
+    /// Distances within the map, without leaving it.
+    pub fn bounded(garden: &Garden) -> Self {
+        Self::tiled(garden, 0)
+    }
+
+    /// Distances across the tiles up to `radius` tiles from the start tile,
+    /// without leaving them. Distances can be longer than on the infinite map
+    /// where the shortest path leaves the tiles, but no path of up to
+    /// `radius` times the shorter side of the map can, so those are exact.
+    pub fn tiled(garden: &Garden, radius: usize) -> Self {
+        let size = 2 * radius + 1;
+        let (rows, cols) = (garden.rows() * size, garden.cols() * size);
+        let start = (
+            radius * garden.rows() + garden.start().0,
+            radius * garden.cols() + garden.start().1,
+        );
+        let rocks = (0..rows * cols)
+            .map(|n| garden.is_rock(n / cols % garden.rows(), n % cols % garden.cols()))
+            .collect();
+        Self {
+            rows,
+            cols,
+            start,
+            rocks,
+            distances: garden.bfs(size, start),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    pub fn is_rock(&self, row: usize, col: usize) -> bool {
+        self.rocks[row * self.cols + col]
+    }
+
+    pub fn distance(&self, row: usize, col: usize) -> Option<usize> {
+        let distance = self.distances[row * self.cols + col];
+        (distance != UNREACHABLE).then_some(distance as usize)
+    }
+
+    pub fn parity(&self, row: usize, col: usize) -> Option<Parity> {
+        self.distance(row, col).map(Parity::of)
+    }
+
+    pub fn max_distance(&self) -> Option<usize> {
+        self.reachable_distances().max()
+    }
+
+    /// Number of reachable plots in each parity class, even first.
+    pub fn parity_counts(&self) -> (usize, usize) {
+        self.reachable_distances()
+            .fold((0, 0), |(even, odd), d| match Parity::of(d) {
+                Parity::Even => (even + 1, odd),
+                Parity::Odd => (even, odd + 1),
+            })
+    }
+
+    /// Whether the elf can end on the plot after exactly `steps` steps.
+    pub fn is_reachable_after(&self, row: usize, col: usize, steps: usize) -> bool {
+        self.distance(row, col)
+            .is_some_and(|d| d <= steps && Parity::of(d) == Parity::of(steps))
+    }
+
+    pub fn reachable_after(&self, steps: usize) -> usize {
+        self.reachable_distances()
+            .filter(|&d| d <= steps && Parity::of(d) == Parity::of(steps))
+            .count()
+    }
+
+    fn reachable_distances(&self) -> impl Iterator<Item = usize> + '_ {
+        self.distances
+            .iter()
+            .filter(|&&d| d != UNREACHABLE)
+            .map(|&d| d as usize)
+    }
+
+    /// The map as in the puzzle, with `O` for each plot the elf can end on
+    /// after `steps` steps.
+    pub fn render_text(&self, steps: usize) -> String {
+        let mut output = String::new();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                output.push(self.symbol(row, col, steps));
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// As [`render_text`](Self::render_text), with each reachable plot
+    /// coloured by its distance using ANSI escape codes.
+    pub fn render_ansi(&self, steps: usize) -> String {
+        let mut output = String::new();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let [r, g, b] = self.colour(row, col, steps);
+                write!(
+                    output,
+                    "\x1b[38;2;{};{};{}m{}",
+                    r,
+                    g,
+                    b,
+                    self.symbol(row, col, steps)
+                )
+                .unwrap();
+            }
+            output.push_str("\x1b[0m\n");
+        }
+        output
+    }
+
+    /// A heatmap as a binary PPM image with one pixel per plot. Plots the elf
+    /// can end on after `steps` steps go from yellow near the start to red,
+    /// and those in range with the other parity are dimmed.
+    pub fn render_ppm(&self, steps: usize) -> Vec<u8> {
+        let mut output = format!("P6\n{} {}\n255\n", self.cols, self.rows).into_bytes();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                output.extend(self.colour(row, col, steps));
+            }
+        }
+        output
+    }
+
+    fn symbol(&self, row: usize, col: usize, steps: usize) -> char {
+        if self.is_rock(row, col) {
+            '#'
+        } else if self.is_reachable_after(row, col, steps) {
+            'O'
+        } else if (row, col) == self.start {
+            'S'
+        } else {
+            '.'
+        }
+    }
+
+    fn colour(&self, row: usize, col: usize, steps: usize) -> [u8; 3] {
+        if self.is_rock(row, col) {
+            return [64, 64, 64];
+        }
+        match self.distance(row, col) {
+            Some(d) if d <= steps => {
+                let heat = 1.0 - d as f64 / steps.max(1) as f64;
+                let colour = [255, (255.0 * heat) as u8, (96.0 * heat) as u8];
+                if Parity::of(d) == Parity::of(steps) {
+                    colour
+                } else {
+                    colour.map(|c| c / 4)
+                }
+            }
+            _ => [0, 0, 0],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn bounded() {
+        let garden = Garden::parse(EXAMPLE);
+        let field = DistanceField::bounded(&garden);
+        assert_eq!(field.distance(5, 5), Some(0));
+        assert_eq!(field.parity(5, 3), Some(Parity::Even));
+        assert_eq!(field.reachable_after(6), 16);
+        assert_eq!(
+            field.render_text(6),
+            "...........\n\
+             .....###.#.\n\
+             .###.##.O#.\n\
+             .O#O#O.O#..\n\
+             O.O.#.#.O..\n\
+             .##O.O####.\n\
+             .##.O#O..#.\n\
+             .O.O.O.##..\n\
+             .##.#.####.\n\
+             .##O.##.##.\n\
+             ...........\n"
+        );
+        let (even, odd) = field.parity_counts();
+        // Every plot can be reached, and far enough out every plot of the
+        // right parity is.
+        assert_eq!(even + odd, 81);
+        assert_eq!(field.reachable_after(100), even);
+        assert_eq!(field.reachable_after(101), odd);
+    }
+
+    #[test]
+    fn tiled() {
+        let garden = Garden::parse(EXAMPLE);
+        let field = DistanceField::tiled(&garden, 5);
+        assert_eq!((field.rows(), field.cols()), (121, 121));
+        assert_eq!(field.start(), (60, 60));
+        for steps in [6, 10, 50] {
+            assert_eq!(
+                field.reachable_after(steps) as u64,
+                garden.reachable_after_tiled(steps).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn render() {
+        let garden = Garden::parse(EXAMPLE);
+        let field = DistanceField::tiled(&garden, 1);
+        let ppm = field.render_ppm(10);
+        let header = b"P6\n33 33\n255\n";
+        assert!(ppm.starts_with(header));
+        assert_eq!(ppm.len(), header.len() + 33 * 33 * 3);
+        let start = header.len() + (16 * 33 + 16) * 3;
+        assert_eq!(ppm[start..start + 3], [255, 255, 96]);
+
+        let ansi = field.render_ansi(10);
+        assert_eq!(ansi.lines().count(), 33);
+        assert_eq!(ansi.matches('O').count(), 50);
+    }
+}
@@ -0,0 +1,768 @@
+use grid::Grid;
+use std::collections::VecDeque;
+
+/// Marks a plot which cannot be reached in distance tables.
+pub(crate) const UNREACHABLE: u32 = u32::MAX;
+
+/// A map of garden plots and rocks, which may be tiled infinitely in every
+/// direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Garden {
+    tiles: Grid<u8>,
+    start: (usize, usize),
+}
+
+impl Garden {
+    pub fn parse(input: &str) -> Self {
+        let lines = input.lines().map(str::as_bytes).collect::<Vec<_>>();
+        let cols = lines[0].len();
+        let tiles = Grid::from_vec(lines.into_iter().flatten().copied().collect(), cols);
+        let start = tiles.indexed_iter().find(|(_, &c)| c == b'S').unwrap().0;
+        Self { tiles, start }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.tiles.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.tiles.cols()
+    }
+
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    pub fn is_rock(&self, row: usize, col: usize) -> bool {
+        self.tiles[(row, col)] == b'#'
+    }
+
+    /// Fewest steps from the start to each plot, without leaving the map.
+    pub fn distances(&self) -> Grid<Option<usize>> {
+        let distances = self.bfs(1, self.start);
+        let mut grid = Grid::new(self.rows(), self.cols());
+        for ((row, col), distance) in grid.indexed_iter_mut() {
+            let d = distances[row * self.cols() + col];
+            *distance = (d != UNREACHABLE).then_some(d as usize);
+        }
+        grid
+    }
+
+    /// Number of plots the elf can end on after exactly `steps` steps,
+    /// without leaving the map.
+    pub fn reachable_after(&self, steps: usize) -> usize {
+        self.distances()
+            .iter()
+            .flatten()
+            .filter(|&&d| d <= steps && (steps - d).is_multiple_of(2))
+            .count()
+    }
+
+    /// Number of plots the elf can end on after exactly `steps` steps on the
+    /// infinitely tiled map. Fails if the distances don't settle into a
+    /// pattern close enough to the start.
+    pub fn reachable_after_tiled(&self, steps: usize) -> Result<u64, String> {
+        Ok(TiledDistances::new(self)?.reachable_after(steps as u64))
+    }
+
+    /// Breadth first search over a square of `size` by `size` tiles, from a
+    /// position within it. Returns the distance to every plot, row by row.
+    pub(crate) fn bfs(&self, size: usize, start: (usize, usize)) -> Vec<u32> {
+        let (rows, cols) = (self.rows() * size, self.cols() * size);
+        let mut distances = vec![UNREACHABLE; rows * cols];
+        let mut queue = VecDeque::from([start]);
+        distances[start.0 * cols + start.1] = 0;
+        while let Some((row, col)) = queue.pop_front() {
+            let distance = distances[row * cols + col];
+            let neighbours = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+            for (row, col) in neighbours {
+                if row >= rows
+                    || col >= cols
+                    || self.is_rock(row % self.rows(), col % self.cols())
+                    || distances[row * cols + col] != UNREACHABLE
+                {
+                    continue;
+                }
+                distances[row * cols + col] = distance + 1;
+                queue.push_back((row, col));
+            }
+        }
+        distances
+    }
+}
+
+/// A number of tiles down and right.
+pub type Offset = (isize, isize);
+
+/// How distances grow heading away from the start in one direction: a plot
+/// `period` tiles further out is always `steps` steps further away, or can
+/// never be reached if `steps` is `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Growth {
+    pub period: Offset,
+    pub steps: Option<u32>,
+}
+
+/// The tiles between two neighbouring directions, `s * a + t * b` tiles
+/// from the start for `s > 0` and `t >= 0`.
+///
+/// Each tile is `i` periods of `a` and `j` of `b` from one of the bases, the
+/// tiles with `s <= 1` and `t < 1`. Tiles with `i` and `j` below the depths
+/// are searched, and the rest are that many periods beyond one that is.
+#[derive(Debug, Clone)]
+struct Cone {
+    a: Growth,
+    b: Growth,
+    bases: Vec<Offset>,
+    depth: (isize, isize),
+}
+
+impl Cone {
+    fn tile(&self, base: Offset, i: isize, j: isize) -> Offset {
+        (
+            base.0 + i * self.a.period.0 + j * self.b.period.0,
+            base.1 + i * self.a.period.1 + j * self.b.period.1,
+        )
+    }
+}
+
+/// Distances from the start to the tiles within `radius` tiles of the start,
+/// and how they grow further out.
+///
+/// Far enough out in any direction, distances grow by a fixed number of
+/// steps every few tiles. How far each of these growths goes per step makes
+/// a polygon, and between two neighbouring corners of it distances grow by
+/// a combination of the two, so the tiles beyond the radius are covered by
+/// one cone for each pair of corners. This is checked on the tiles where
+/// each cone starts to grow, and the radius grown until it holds.
+#[derive(Debug, Clone)]
+pub struct TiledDistances {
+    garden: Garden,
+    radius: usize,
+    /// The plots around the edge of a tile, row by row.
+    edge: Vec<usize>,
+    /// Distances to the edge plots of every tile up to one tile beyond the
+    /// radius, tile by tile. Any path into a tile crosses its edge, so these
+    /// fix the distances to the rest of it.
+    edges: Vec<u32>,
+    /// The corners, and the axes, in order of angle.
+    growth: Vec<Growth>,
+    cones: Vec<Cone>,
+}
+
+impl TiledDistances {
+    /// Random gardens settle within about `rows + cols` tiles of the start,
+    /// so give up well beyond that.
+    pub fn new(garden: &Garden) -> Result<Self, String> {
+        Self::within(garden, (garden.rows() + garden.cols()) * 3 / 2 + 16)
+    }
+
+    fn within(garden: &Garden, max_radius: usize) -> Result<Self, String> {
+        let mut radius = 2.min(max_radius);
+        loop {
+            if let Some(distances) = Self::with_radius(garden, radius) {
+                return Ok(distances);
+            }
+            if radius == max_radius {
+                return Err(format!(
+                    "distances do not settle within {} tiles of the start",
+                    max_radius
+                ));
+            }
+            radius = (radius + radius / 2).min(max_radius);
+        }
+    }
+
+    fn with_radius(garden: &Garden, radius: usize) -> Option<Self> {
+        let (rows, cols) = (garden.rows(), garden.cols());
+        let edge = (0..rows * cols)
+            .filter(|cell| {
+                let (row, col) = (cell / cols, cell % cols);
+                row == 0 || row == rows - 1 || col == 0 || col == cols - 1
+            })
+            .collect::<Vec<_>>();
+        let mut result = Self {
+            garden: garden.clone(),
+            radius,
+            edges: search(garden, radius, &edge),
+            edge,
+            growth: vec![],
+            cones: vec![],
+        };
+        result.growth = result.corners()?;
+        let n = result.growth.len();
+        result.cones = (0..n)
+            .map(|k| result.cone(result.growth[k], result.growth[(k + 1) % n]))
+            .collect::<Option<_>>()?;
+        Some(result)
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    /// Growth towards each corner of the polygon, and along each axis.
+    pub fn growth(&self) -> &[Growth] {
+        &self.growth
+    }
+
+    /// Distances to the edge of the tile `i` tiles down and `j` tiles right
+    /// of the start.
+    fn edge(&self, (i, j): Offset) -> &[u32] {
+        let r = self.radius as isize;
+        debug_assert!(i.abs() <= r && j.abs() <= r);
+        let size = 2 * r + 3;
+        let tile = ((i + r + 1) * size + j + r + 1) as usize;
+        &self.edges[tile * self.edge.len()..(tile + 1) * self.edge.len()]
+    }
+
+    /// Distances to every plot of a tile, from the shortest paths within it
+    /// from each edge plot, and from the start.
+    fn tile(&self, offset: Offset) -> Vec<u32> {
+        let (rows, cols) = (self.garden.rows(), self.garden.cols());
+        let mut entries = self
+            .edge
+            .iter()
+            .zip(self.edge(offset))
+            .filter(|&(_, &d)| d != UNREACHABLE)
+            .map(|(&cell, &d)| (d, cell))
+            .collect::<Vec<_>>();
+        if offset == (0, 0) {
+            entries.push((0, self.garden.start.0 * cols + self.garden.start.1));
+        }
+        entries.sort_unstable();
+
+        let mut distances = vec![UNREACHABLE; rows * cols];
+        let mut entries = entries.into_iter().peekable();
+        let mut queue = VecDeque::new();
+        loop {
+            let next = match (queue.front(), entries.peek()) {
+                (Some(&(d, _)), Some(&(e, _))) if e < d => entries.next(),
+                (Some(_), _) => queue.pop_front(),
+                (None, _) => entries.next(),
+            };
+            let Some((d, cell)) = next else {
+                return distances;
+            };
+            if distances[cell] <= d {
+                continue;
+            }
+            distances[cell] = d;
+            let (row, col) = (cell / cols, cell % cols);
+            let neighbours = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+            for (row, col) in neighbours {
+                if row < rows && col < cols && !self.garden.is_rock(row, col) {
+                    queue.push_back((d + 1, row * cols + col));
+                }
+            }
+        }
+    }
+
+    /// Whether every plot one period on from `tile` is as many steps
+    /// further as the growth, or can't be reached either. Only the edges
+    /// need checking, as they fix the rest of both tiles.
+    fn follows(&self, tile: Offset, growth: Growth) -> bool {
+        let next = (tile.0 + growth.period.0, tile.1 + growth.period.1);
+        matches!(
+            growth_between(self.edge(tile), self.edge(next)),
+            Some(steps) if steps.is_none() || steps == growth.steps
+        )
+    }
+
+    /// Growth in every direction with a period up to a quarter of the radius,
+    /// keeping those at the corners of the polygon and on the axes. Every
+    /// axis must have settled.
+    fn corners(&self) -> Option<Vec<Growth>> {
+        let reach = (self.radius / 4).max(1) as isize;
+        let mut periods = (-reach..=reach)
+            .flat_map(|i| (-reach..=reach).map(move |j| (i, j)))
+            .filter(|&period| period != (0, 0))
+            .collect::<Vec<_>>();
+        periods.sort_by_key(|&(i, j)| i.abs().max(j.abs()));
+        let mut settled: Vec<Growth> = vec![];
+        for period in periods {
+            if settled.iter().any(|g| same_direction(g.period, period)) {
+                continue;
+            }
+            if let Some(growth) = self.ray_growth(period) {
+                settled.push(growth);
+            }
+        }
+
+        let mut growth = convex_hull(
+            settled
+                .iter()
+                .filter(|growth| growth.steps.is_some())
+                .copied()
+                .collect(),
+        );
+        for axis in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let growth_along = settled
+                .iter()
+                .find(|g| same_direction(g.period, axis))
+                .copied()?;
+            if !growth.contains(&growth_along) {
+                growth.push(growth_along);
+            }
+        }
+        growth.sort_by(|a, b| angle(a.period).total_cmp(&angle(b.period)));
+        Some(growth)
+    }
+
+    /// Number of periods out along `period` that fit within the radius.
+    fn reach(&self, period: Offset) -> isize {
+        self.radius as isize / extent(period)
+    }
+
+    /// The growth along `period`, if the three outermost tiles that way are
+    /// each the same number of steps further than the one before.
+    fn ray_growth(&self, period: Offset) -> Option<Growth> {
+        let last = self.reach(period);
+        if last < 3 {
+            return None;
+        }
+        let [inner, middle, outer] =
+            [last - 2, last - 1, last].map(|k| self.edge((k * period.0, k * period.1)));
+        let steps = growth_between(inner, middle)?;
+        (growth_between(middle, outer)? == steps).then_some(Growth { period, steps })
+    }
+
+    /// Number of periods out along the growth from which every tile follows it.
+    fn onset(&self, growth: Growth) -> isize {
+        let (i, j) = growth.period;
+        (1..self.reach(growth.period))
+            .rev()
+            .take_while(|&k| self.follows((k * i, k * j), growth))
+            .last()
+            .unwrap_or(1)
+    }
+
+    /// The cone between two growths, searched deep enough that the next two
+    /// periods out each way follow the growth. Starts from where the
+    /// distances along each edge settle, and goes deeper on any side that
+    /// has not, as long as the radius allows.
+    fn cone(&self, a: Growth, b: Growth) -> Option<Cone> {
+        let det = cross(a.period, b.period);
+        let corners = [
+            (0, 0),
+            a.period,
+            b.period,
+            (a.period.0 + b.period.0, a.period.1 + b.period.1),
+        ];
+        let (rows, cols) = (corners.map(|c| c.0), corners.map(|c| c.1));
+        let (rows, cols) = (
+            *rows.iter().min().unwrap()..=*rows.iter().max().unwrap(),
+            *cols.iter().min().unwrap()..=*cols.iter().max().unwrap(),
+        );
+        let bases = rows
+            .flat_map(|i| cols.clone().map(move |j| (i, j)))
+            .filter(|&base| {
+                let (s, t) = (cross(base, b.period), cross(a.period, base));
+                0 < s && s <= det && 0 <= t && t < det
+            })
+            .collect::<Vec<_>>();
+        let spare = self.radius as isize - bases.iter().map(|&base| extent(base)).max()?;
+
+        let mut cone = Cone {
+            a,
+            b,
+            bases,
+            depth: (self.onset(a), self.onset(b)),
+        };
+        loop {
+            let (i, j) = cone.depth;
+            if (i + 2) * extent(a.period) + (j + 2) * extent(b.period) > spare {
+                return None;
+            }
+            match self.unsettled(&cone) {
+                (false, false) => return Some(cone),
+                (deeper_a, deeper_b) => {
+                    cone.depth = (i + deeper_a as isize, j + deeper_b as isize);
+                }
+            }
+        }
+    }
+
+    /// Whether the tiles at the depth of the cone, or one period beyond,
+    /// fail to follow the growth along `a`, and along `b`.
+    fn unsettled(&self, cone: &Cone) -> (bool, bool) {
+        let (d, e) = cone.depth;
+        let follow = |i: isize, j: isize, growth: Growth| {
+            cone.bases
+                .iter()
+                .all(|&base| self.follows(cone.tile(base, i, j), growth))
+        };
+        let along_a = (0..e).all(|j| follow(d, j, cone.a) && follow(d + 1, j, cone.a));
+        let along_b = (0..d).all(|i| follow(i, e, cone.b) && follow(i, e + 1, cone.b));
+        let inside = follow(d, e, cone.a)
+            && follow(d + 1, e, cone.a)
+            && follow(d, e, cone.b)
+            && follow(d, e + 1, cone.b)
+            && follow(d + 1, e, cone.b);
+        (!(along_a && inside), !(along_b && inside))
+    }
+
+    /// Number of plots reachable in exactly `steps` steps: those at most that
+    /// far away, with the same parity.
+    pub fn reachable_after(&self, steps: u64) -> u64 {
+        let within = |d: u32| {
+            (d != UNREACHABLE)
+                .then(|| steps.checked_sub(d as u64))
+                .flatten()
+        };
+        let count = |tile: Offset, count_from: &dyn Fn(u64) -> u64| {
+            self.tile(tile)
+                .into_iter()
+                .filter_map(within)
+                .map(count_from)
+                .sum::<u64>()
+        };
+        let growth = |growth: Growth| growth.steps.map(u64::from);
+
+        let mut total = count((0, 0), &count_here);
+        for cone in &self.cones {
+            let (a, b) = (growth(cone.a), growth(cone.b));
+            let (d, e) = cone.depth;
+            for &base in &cone.bases {
+                for i in 0..=d {
+                    for j in 0..=e {
+                        let tile = cone.tile(base, i, j);
+                        // At the depth, count every period further out too.
+                        total += match (i == d, j == e) {
+                            (false, false) => count(tile, &count_here),
+                            (true, false) => count(tile, &|m| count_ray(m, a)),
+                            (false, true) => count(tile, &|m| count_ray(m, b)),
+                            (true, true) => count(tile, &|m| count_cone(m, a, b)),
+                        };
+                    }
+                }
+            }
+        }
+        total
+    }
+}
+
+/// Breadth first search over the tiles within `radius` tiles of the start,
+/// and one more so that the distances to the outermost tiles are not cut
+/// short by the edge of the search. Only the distances to `edge` plots of
+/// each tile are kept, tile by tile.
+fn search(garden: &Garden, radius: usize, edge: &[usize]) -> Vec<u32> {
+    let size = 2 * radius + 3;
+    let (rows, cols) = (garden.rows(), garden.cols());
+    let mut index = vec![None; rows * cols];
+    for (k, &cell) in edge.iter().enumerate() {
+        index[cell] = Some(k);
+    }
+    let rocks = (0..rows * cols)
+        .map(|cell| garden.is_rock(cell / cols, cell % cols))
+        .collect::<Vec<_>>();
+
+    // Plots are numbered tile by tile, and found by tile then row and column
+    // within the tile.
+    let plot = |(tile, row, col): (usize, usize, usize)| (tile * rows + row) * cols + col;
+    let mut edges = vec![UNREACHABLE; size * size * edge.len()];
+    let mut seen = vec![0u64; (size * size * rows * cols).div_ceil(64)];
+    let start = ((radius + 1) * (size + 1), garden.start.0, garden.start.1);
+    seen[plot(start) / 64] |= 1 << (plot(start) % 64);
+    let mut frontier = vec![start];
+    let mut next = vec![];
+    for distance in 0.. {
+        if frontier.is_empty() {
+            break;
+        }
+        for &(tile, row, col) in &frontier {
+            if let Some(k) = index[row * cols + col] {
+                edges[tile * edge.len() + k] = distance;
+            }
+            let neighbours = [
+                match row {
+                    0 => (tile.wrapping_sub(size), rows - 1, col),
+                    _ => (tile, row - 1, col),
+                },
+                match row + 1 {
+                    r if r == rows => (tile + size, 0, col),
+                    r => (tile, r, col),
+                },
+                match col {
+                    0 if tile % size == 0 => (usize::MAX, row, 0),
+                    0 => (tile - 1, row, cols - 1),
+                    _ => (tile, row, col - 1),
+                },
+                match col + 1 {
+                    c if c == cols && (tile + 1) % size == 0 => (usize::MAX, row, 0),
+                    c if c == cols => (tile + 1, row, 0),
+                    c => (tile, row, c),
+                },
+            ];
+            for neighbour in neighbours {
+                let (tile, row, col) = neighbour;
+                if tile >= size * size || rocks[row * cols + col] {
+                    continue;
+                }
+                let plot = plot(neighbour);
+                if seen[plot / 64] & 1 << (plot % 64) != 0 {
+                    continue;
+                }
+                seen[plot / 64] |= 1 << (plot % 64);
+                next.push(neighbour);
+            }
+        }
+        std::mem::swap(&mut frontier, &mut next);
+        next.clear();
+    }
+    edges
+}
+
+/// The number of steps every plot of `outer` is further than in `inner`, or
+/// `Some(None)` if no plots of either can be reached.
+fn growth_between(inner: &[u32], outer: &[u32]) -> Option<Option<u32>> {
+    let mut growth = None;
+    for (&a, &b) in inner.iter().zip(outer) {
+        if a == UNREACHABLE || b == UNREACHABLE {
+            if a != b {
+                return None;
+            }
+            continue;
+        }
+        let step = b.checked_sub(a).filter(|&step| step > 0)?;
+        if *growth.get_or_insert(step) != step {
+            return None;
+        }
+    }
+    Some(growth)
+}
+
+/// The growths at the corners of the convex hull of `period / steps`, which
+/// is how far each one goes per step.
+fn convex_hull(mut growths: Vec<Growth>) -> Vec<Growth> {
+    let point = |g: &Growth| (g.period, g.steps.unwrap() as isize);
+    growths.sort_by(|a, b| {
+        let ((p, s), (q, t)) = (point(a), point(b));
+        (p.1 * t).cmp(&(q.1 * s)).then((p.0 * t).cmp(&(q.0 * s)))
+    });
+    // Sign of the turn from `a` to `b` to `c`, scaled by positive steps.
+    let turn = |a: &Growth, b: &Growth, c: &Growth| {
+        let ((p, s), (q, t), (r, u)) = (point(a), point(b), point(c));
+        let ab = (s * q.0 - t * p.0, s * q.1 - t * p.1);
+        let ac = (s * r.0 - u * p.0, s * r.1 - u * p.1);
+        cross(ab, ac)
+    };
+    let mut hull: Vec<Growth> = vec![];
+    for pass in [growths.clone(), growths.into_iter().rev().collect()] {
+        let start = hull.len();
+        for growth in pass {
+            while hull.len() >= start + 2
+                && turn(&hull[hull.len() - 2], &hull[hull.len() - 1], &growth) <= 0
+            {
+                hull.pop();
+            }
+            hull.push(growth);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+fn cross(a: Offset, b: Offset) -> isize {
+    a.1 * b.0 - a.0 * b.1
+}
+
+fn extent((i, j): Offset) -> isize {
+    i.abs().max(j.abs())
+}
+
+fn angle((i, j): Offset) -> f64 {
+    (i as f64).atan2(j as f64)
+}
+
+fn same_direction(a: Offset, b: Offset) -> bool {
+    cross(a, b) == 0 && a.0 * b.0 + a.1 * b.1 > 0
+}
+
+/// Whether a plot `m` steps short of the limit can be reached, on parity.
+fn count_here(m: u64) -> u64 {
+    m.is_multiple_of(2) as u64
+}
+
+/// Number of `a >= 0` with `a * p <= m`, and `a * p` the same parity as `m`.
+fn count_ray(m: u64, p: Option<u64>) -> u64 {
+    count_here(m) + p.map_or(0, |p| count_line(m, p))
+}
+
+/// Number of `a, b >= 0` with `a * p + b * q <= m`, and `a * p + b * q` the
+/// same parity as `m`. A growth of `None` only allows zero.
+fn count_cone(m: u64, p: Option<u64>, q: Option<u64>) -> u64 {
+    let both = p.zip(q).map_or(0, |(p, q)| count_quadrant(m, p, q));
+    count_ray(m, p) + q.map_or(0, |q| count_line(m, q)) + both
+}
+
+/// Number of `a >= 1` with `a * p <= m`, and `a * p` the same parity as `m`.
+fn count_line(m: u64, p: u64) -> u64 {
+    (0..2)
+        .filter(|alpha| (alpha * p + m).is_multiple_of(2))
+        .filter_map(|alpha| {
+            // a = 2x + alpha, where x starts from 1 if alpha is 0.
+            let n = m.checked_sub(alpha * p + if alpha == 0 { 2 * p } else { 0 })?;
+            Some(n / (2 * p) + 1)
+        })
+        .sum()
+}
+
+/// Number of `a, b >= 1` with `a * p + b * q <= m`, and `a * p + b * q` the
+/// same parity as `m`.
+fn count_quadrant(m: u64, p: u64, q: u64) -> u64 {
+    let mut count = 0;
+    for alpha in 0..2 {
+        for beta in 0..2 {
+            if !(alpha * p + beta * q + m).is_multiple_of(2) {
+                continue;
+            }
+            // a = 2x + alpha and b = 2y + beta, where x and y start from 1
+            // if alpha or beta are 0.
+            let skip = alpha * p + beta * q + (1 - alpha) * 2 * p + (1 - beta) * 2 * q;
+            if let Some(n) = m.checked_sub(skip) {
+                count += count_triangle(n, 2 * p, 2 * q);
+            }
+        }
+    }
+    count
+}
+
+/// Number of `x, y >= 0` with `a * x + b * y <= n`.
+fn count_triangle(n: u64, a: u64, b: u64) -> u64 {
+    let xs = n / a + 1;
+    xs + floor_sum(xs, b, a, n % a)
+}
+
+/// Sum of `(a * i + b) / m` for `i` in `0..n`.
+fn floor_sum(mut n: u64, mut m: u64, mut a: u64, mut b: u64) -> u64 {
+    let mut sum = 0;
+    loop {
+        if a >= m {
+            sum += n * (n - 1) / 2 * (a / m);
+            a %= m;
+        }
+        if b >= m {
+            sum += n * (b / m);
+            b %= m;
+        }
+        let y_max = a * n + b;
+        if y_max < m {
+            return sum;
+        }
+        n = y_max / m;
+        b = y_max % m;
+        std::mem::swap(&mut m, &mut a);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance_field::DistanceField;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn finite() {
+        let garden = Garden::parse(EXAMPLE);
+        assert_eq!(garden.start(), (5, 5));
+        assert_eq!(garden.reachable_after(6), 16);
+        assert_eq!(garden.distances()[(5, 4)], Some(1));
+        assert_eq!(garden.distances()[(1, 5)], None);
+    }
+
+    #[test]
+    fn tiled() {
+        let garden = Garden::parse(EXAMPLE);
+        for (steps, expected) in [
+            (6, 16),
+            (10, 50),
+            (50, 1594),
+            (100, 6536),
+            (500, 167004),
+            (1000, 668697),
+            (5000, 16733044),
+        ] {
+            assert_eq!(
+                garden.reachable_after_tiled(steps),
+                Ok(expected),
+                "{} steps",
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn off_centre_start() {
+        // The same garden, shifted so the start is near a corner.
+        let garden = Garden::parse(EXAMPLE);
+        let shifted = (0..garden.rows())
+            .map(|row| {
+                let line = EXAMPLE.lines().nth((row + 3) % garden.rows()).unwrap();
+                let (left, right) = line.split_at(3);
+                format!("{}{}", right, left)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let shifted = Garden::parse(&shifted);
+        assert_eq!(shifted.start(), (2, 2));
+        for steps in [7, 64, 333] {
+            assert_eq!(
+                shifted.reachable_after_tiled(steps),
+                garden.reachable_after_tiled(steps)
+            );
+        }
+    }
+
+    #[test]
+    fn unsettled() {
+        // Without a straight path through, it is quicker to cut diagonally
+        // across tiles than to go down and then across.
+        let garden = Garden::parse("...#.#\n.##...\n.##.#.\n#.....\n###..S\n......\n#...##");
+        assert_eq!(
+            TiledDistances::within(&garden, 4).unwrap_err(),
+            "distances do not settle within 4 tiles of the start"
+        );
+        let distances = TiledDistances::new(&garden).unwrap();
+        let periods = distances.growth().iter().map(|growth| growth.period);
+        assert_eq!(
+            periods.collect::<Vec<_>>(),
+            [(-1, 0), (-1, 1), (0, 1), (1, 0), (1, -1), (0, -1)]
+        );
+
+        let field = DistanceField::tiled(&garden, 60);
+        for steps in [50, 51, 100, 200, 333, 360] {
+            assert_eq!(
+                distances.reachable_after(steps as u64),
+                field.reachable_after(steps) as u64,
+                "{} steps",
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn counting() {
+        assert_eq!(count_line(10, 3), 1);
+        assert_eq!(count_line(12, 3), 2);
+        assert_eq!(count_line(12, 2), 6);
+        for (m, p, q) in [(20u64, 3, 5), (21, 3, 5), (40, 4, 4), (7, 11, 13)] {
+            let brute = (1..=m)
+                .flat_map(|a| (1..=m).map(move |b| a * p + b * q))
+                .filter(|&sum| sum <= m && (sum + m).is_multiple_of(2))
+                .count() as u64;
+            assert_eq!(count_quadrant(m, p, q), brute, "{} {} {}", m, p, q);
+        }
+    }
+}
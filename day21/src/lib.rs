@@ -1,3 +1,5 @@
+pub mod distance_field;
+pub mod garden;
 pub mod part1;
 pub mod part2;
 
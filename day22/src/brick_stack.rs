@@ -0,0 +1,415 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Brick {
+    pub x: Range<usize>,
+    pub y: Range<usize>,
+    pub z: Range<usize>,
+}
+
+impl Brick {
+    pub fn parse(line: &str) -> Self {
+        let (lhs, rhs) = line.split_once('~').unwrap();
+        let lhs = lhs.split(',').map(|s| s.parse::<usize>().unwrap());
+        let rhs = rhs.split(',').map(|s| s.parse::<usize>().unwrap());
+        let mut ranges = lhs.zip(rhs).map(|(a, b)| a.min(b)..a.max(b) + 1);
+        Self {
+            x: ranges.next().unwrap(),
+            y: ranges.next().unwrap(),
+            z: ranges.next().unwrap(),
+        }
+    }
+
+    /// The (x, y) cells under the brick.
+    pub fn footprint(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.x
+            .clone()
+            .flat_map(|x| self.y.clone().map(move |y| (x, y)))
+    }
+}
+
+/// A stack of bricks after they have all fallen as far as they can, and which
+/// bricks rest on which. Bricks keep their position from the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrickStack {
+    bricks: Vec<Brick>,
+    /// Bricks directly on top of each brick.
+    supports: Vec<Vec<usize>>,
+    /// Bricks directly under each brick.
+    supported_by: Vec<Vec<usize>>,
+    /// Bricks from the lowest to the highest.
+    order: Vec<usize>,
+}
+
+impl BrickStack {
+    pub fn parse(input: &str) -> Self {
+        Self::new(input.lines().map(Brick::parse).collect())
+    }
+
+    /// Settle the bricks, dropping each in turn from the lowest up onto a
+    /// height map of the tops of the bricks below.
+    pub fn new(mut bricks: Vec<Brick>) -> Self {
+        let mut order = (0..bricks.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&n| bricks[n].z.start);
+
+        let cols = bricks.iter().map(|brick| brick.y.end).max().unwrap_or(0);
+        let rows = bricks.iter().map(|brick| brick.x.end).max().unwrap_or(0);
+        // Height of the top of the stack over each cell, and the brick there.
+        let mut heights: Vec<(usize, Option<usize>)> = vec![(1, None); rows * cols];
+        let mut supports = vec![vec![]; bricks.len()];
+        let mut supported_by = vec![vec![]; bricks.len()];
+
+        for &n in &order {
+            let cells = bricks[n]
+                .footprint()
+                .map(|(x, y)| x * cols + y)
+                .collect::<Vec<_>>();
+            let floor = cells.iter().map(|&cell| heights[cell].0).max().unwrap();
+            for &cell in &cells {
+                if let (height, Some(below)) = heights[cell] {
+                    if height == floor && !supported_by[n].contains(&below) {
+                        supported_by[n].push(below);
+                        supports[below].push(n);
+                    }
+                }
+            }
+            let brick = &mut bricks[n];
+            brick.z = floor..floor + brick.z.len();
+            for cell in cells {
+                heights[cell] = (brick.z.end, Some(n));
+            }
+        }
+
+        Self {
+            bricks,
+            supports,
+            supported_by,
+            order,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bricks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bricks.is_empty()
+    }
+
+    /// The settled bricks, in the order they were given.
+    pub fn bricks(&self) -> &[Brick] {
+        &self.bricks
+    }
+
+    /// Bricks resting directly on the given brick.
+    pub fn supports(&self, brick: usize) -> &[usize] {
+        &self.supports[brick]
+    }
+
+    /// Bricks the given brick rests directly on, which is none for bricks on
+    /// the ground.
+    pub fn supported_by(&self, brick: usize) -> &[usize] {
+        &self.supported_by[brick]
+    }
+
+    /// Check whether the brick can be removed without any other brick falling.
+    pub fn is_safe_to_disintegrate(&self, brick: usize) -> bool {
+        self.supports[brick]
+            .iter()
+            .all(|&other| self.supported_by[other].len() > 1)
+    }
+
+    /// The closest brick which every path of support from the ground to the
+    /// given brick passes through, or `None` if the ground is the only one.
+    /// Removing a brick makes exactly the bricks it dominates fall.
+    pub fn dominators(&self) -> Vec<Option<usize>> {
+        let mut dominators = vec![None; self.len()];
+        let mut depths = vec![0; self.len()];
+        // Supporters are always lower, so come first.
+        for &n in &self.order {
+            let dominator = self.supported_by[n]
+                .iter()
+                .map(|&brick| Some(brick))
+                .reduce(|a, b| common_dominator(&dominators, &depths, a, b))
+                .flatten();
+            dominators[n] = dominator;
+            depths[n] = dominator.map_or(1, |d| depths[d] + 1);
+        }
+        dominators
+    }
+
+    /// Number of other bricks which would fall if each brick were removed.
+    pub fn chain_reactions(&self) -> Vec<usize> {
+        let dominators = self.dominators();
+        let mut counts = vec![0; self.len()];
+        for &n in self.order.iter().rev() {
+            if let Some(dominator) = dominators[n] {
+                counts[dominator] += counts[n] + 1;
+            }
+        }
+        counts
+    }
+
+    /// Bricks which cannot be removed without another brick falling.
+    pub fn load_bearing(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&brick| !self.is_safe_to_disintegrate(brick))
+            .collect()
+    }
+
+    /// Bricks which would fall if the given bricks were removed.
+    pub fn falling(&self, removed: &[usize]) -> Vec<usize> {
+        let mut gone = vec![false; self.len()];
+        for &brick in removed {
+            gone[brick] = true;
+        }
+        let mut falling = vec![];
+        for &n in &self.order {
+            let supporters = &self.supported_by[n];
+            if !gone[n] && !supporters.is_empty() && supporters.iter().all(|&b| gone[b]) {
+                gone[n] = true;
+                falling.push(n);
+            }
+        }
+        falling.sort_unstable();
+        falling
+    }
+
+    /// Fewest bricks to remove to make the given brick fall, or `None` for a
+    /// brick on the ground. This is a minimum vertex cut between the ground
+    /// and the brick, found from a maximum flow through the bricks below it.
+    pub fn minimal_removals(&self, target: usize) -> Option<Vec<usize>> {
+        if self.supported_by[target].is_empty() {
+            return None;
+        }
+        // Each brick is split into an entry and an exit, joined by an edge of
+        // capacity 1, with the ground as the source and the target's entry as
+        // the sink.
+        let source = 2 * self.len();
+        let mut flow = Flow::new(source + 1);
+        for n in 0..self.len() {
+            if n != target {
+                flow.add_edge(2 * n, 2 * n + 1, 1);
+            }
+            if self.supported_by[n].is_empty() {
+                flow.add_edge(source, 2 * n, usize::MAX);
+            }
+            for &above in &self.supports[n] {
+                flow.add_edge(2 * n + 1, 2 * above, usize::MAX);
+            }
+        }
+        flow.max_flow(source, 2 * target);
+        // Cut as close to the target as possible, so fewer other bricks fall.
+        let reaching = flow.reaching(2 * target);
+        Some(
+            (0..self.len())
+                .filter(|&n| !reaching[2 * n] && reaching[2 * n + 1])
+                .collect(),
+        )
+    }
+
+    /// The stack with the given bricks removed, after the rest have fallen.
+    /// The remaining bricks keep their order.
+    pub fn without(&self, removed: &[usize]) -> Self {
+        Self::new(
+            (0..self.len())
+                .filter(|n| !removed.contains(n))
+                .map(|n| self.bricks[n].clone())
+                .collect(),
+        )
+    }
+
+    /// Height of the top of the stack over each (x, y) cell, indexed by x and
+    /// then y, with 0 for the ground.
+    pub fn height_profile(&self) -> Vec<Vec<usize>> {
+        let rows = self
+            .bricks
+            .iter()
+            .map(|brick| brick.x.end)
+            .max()
+            .unwrap_or(0);
+        let cols = self
+            .bricks
+            .iter()
+            .map(|brick| brick.y.end)
+            .max()
+            .unwrap_or(0);
+        let mut profile = vec![vec![0; cols]; rows];
+        for brick in &self.bricks {
+            for (x, y) in brick.footprint() {
+                profile[x][y] = profile[x][y].max(brick.z.end - 1);
+            }
+        }
+        profile
+    }
+}
+
+/// A flow network with residual capacities, for finding minimum cuts.
+struct Flow {
+    /// Destination and remaining capacity of each edge, stored in pairs with
+    /// their reverse edges.
+    edges: Vec<(usize, usize)>,
+    adjacent: Vec<Vec<usize>>,
+}
+
+impl Flow {
+    fn new(nodes: usize) -> Self {
+        Self {
+            edges: vec![],
+            adjacent: vec![vec![]; nodes],
+        }
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, capacity: usize) {
+        self.adjacent[from].push(self.edges.len());
+        self.edges.push((to, capacity));
+        self.adjacent[to].push(self.edges.len());
+        self.edges.push((from, 0));
+    }
+
+    /// Push as much flow as possible along shortest augmenting paths.
+    fn max_flow(&mut self, source: usize, sink: usize) {
+        loop {
+            let mut parents = vec![None; self.adjacent.len()];
+            let mut seen = vec![false; self.adjacent.len()];
+            let mut queue = VecDeque::from([source]);
+            seen[source] = true;
+            while let Some(node) = queue.pop_front() {
+                for &edge in &self.adjacent[node] {
+                    let (next, capacity) = self.edges[edge];
+                    if capacity > 0 && !seen[next] {
+                        seen[next] = true;
+                        parents[next] = Some(edge);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            if !seen[sink] {
+                return;
+            }
+            let mut path = vec![];
+            let mut node = sink;
+            while let Some(edge) = parents[node] {
+                path.push(edge);
+                node = self.edges[edge ^ 1].0;
+            }
+            let amount = path.iter().map(|&edge| self.edges[edge].1).min().unwrap();
+            for edge in path {
+                self.edges[edge].1 -= amount;
+                self.edges[edge ^ 1].1 = self.edges[edge ^ 1].1.saturating_add(amount);
+            }
+        }
+    }
+
+    /// Nodes which can still reach the sink through edges with capacity left.
+    fn reaching(&self, sink: usize) -> Vec<bool> {
+        let mut reaching = vec![false; self.adjacent.len()];
+        let mut queue = VecDeque::from([sink]);
+        reaching[sink] = true;
+        while let Some(node) = queue.pop_front() {
+            for &edge in &self.adjacent[node] {
+                let previous = self.edges[edge].0;
+                if self.edges[edge ^ 1].1 > 0 && !reaching[previous] {
+                    reaching[previous] = true;
+                    queue.push_back(previous);
+                }
+            }
+        }
+        reaching
+    }
+}
+
+/// Lowest common ancestor of two bricks in the dominator tree.
+fn common_dominator(
+    dominators: &[Option<usize>],
+    depths: &[usize],
+    mut a: Option<usize>,
+    mut b: Option<usize>,
+) -> Option<usize> {
+    let depth = |brick: Option<usize>| brick.map_or(0, |n| depths[n]);
+    while a != b {
+        if depth(a) >= depth(b) {
+            a = a.and_then(|n| dominators[n]);
+        } else {
+            b = b.and_then(|n| dominators[n]);
+        }
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn settle() {
+        let stack = BrickStack::parse(EXAMPLE);
+        let heights = stack
+            .bricks()
+            .iter()
+            .map(|brick| brick.z.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(heights, vec![1..2, 2..3, 2..3, 3..4, 3..4, 4..5, 5..7]);
+        assert_eq!(stack.supports(0), [1, 2]);
+        assert_eq!(stack.supported_by(3), [1, 2]);
+        assert_eq!(stack.supported_by(0), [] as [usize; 0]);
+        let safe = (0..stack.len())
+            .filter(|&n| stack.is_safe_to_disintegrate(n))
+            .count();
+        assert_eq!(safe, 5);
+    }
+
+    #[test]
+    fn chain_reactions() {
+        let stack = BrickStack::parse(EXAMPLE);
+        assert_eq!(
+            stack.dominators(),
+            vec![None, Some(0), Some(0), Some(0), Some(0), Some(0), Some(5)]
+        );
+        assert_eq!(stack.chain_reactions(), vec![6, 0, 0, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn queries() {
+        let stack = BrickStack::parse(EXAMPLE);
+        assert_eq!(stack.load_bearing(), vec![0, 5]);
+        assert_eq!(stack.falling(&[1, 2]), vec![3, 4, 5, 6]);
+        assert_eq!(stack.minimal_removals(0), None);
+        assert_eq!(stack.minimal_removals(3), Some(vec![0]));
+        assert_eq!(stack.minimal_removals(6), Some(vec![5]));
+        assert_eq!(
+            stack.height_profile(),
+            vec![vec![3, 4, 3], vec![2, 6, 2], vec![3, 4, 3]]
+        );
+        assert_eq!(
+            stack.without(&[5]).height_profile(),
+            vec![vec![3, 3, 3], vec![2, 3, 2], vec![3, 3, 3]]
+        );
+    }
+
+    #[test]
+    fn minimal_removals() {
+        // Two separate columns hold up the top brick, so one brick from each
+        // has to go.
+        let stack =
+            BrickStack::parse("0,0,1~0,0,1\n2,0,1~2,0,1\n0,0,2~0,0,2\n2,0,2~2,0,2\n0,0,3~2,0,3");
+        let removals = stack.minimal_removals(4).unwrap();
+        assert_eq!(removals.len(), 2);
+        assert_eq!(stack.falling(&removals), vec![4]);
+    }
+
+    #[test]
+    fn diamond() {
+        // Two bricks on one, both holding up a fourth, with a fifth on top.
+        let stack =
+            BrickStack::parse("0,0,1~1,0,1\n0,0,2~0,0,2\n1,0,2~1,0,2\n0,0,3~1,0,3\n0,0,4~0,0,4");
+        assert_eq!(
+            stack.dominators(),
+            vec![None, Some(0), Some(0), Some(0), Some(3)]
+        );
+        assert_eq!(stack.chain_reactions(), vec![4, 0, 0, 1, 0]);
+    }
+}
@@ -1,5 +1,7 @@
+pub mod brick_stack;
 pub mod part1;
 pub mod part2;
+pub mod render;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
@@ -0,0 +1,87 @@
+use crate::brick_stack::BrickStack;
+use std::fmt::Write;
+
+/// Corners of a unit box, by whether each of x, y and z is at the far side.
+const FACES: [[usize; 4]; 6] = [
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+];
+
+/// The settled stack as a Wavefront OBJ model, with one box object per brick
+/// and z pointing up.
+pub fn render_obj(stack: &BrickStack) -> String {
+    let mut output = String::new();
+    for (n, brick) in stack.bricks().iter().enumerate() {
+        writeln!(output, "o brick{}", n).unwrap();
+        for corner in 0..8 {
+            let pick = |range: &std::ops::Range<usize>, far: bool| {
+                if far {
+                    range.end
+                } else {
+                    range.start
+                }
+            };
+            writeln!(
+                output,
+                "v {} {} {}",
+                pick(&brick.x, corner & 1 != 0),
+                pick(&brick.y, corner & 2 != 0),
+                pick(&brick.z, corner & 4 != 0)
+            )
+            .unwrap();
+        }
+        for face in FACES {
+            let [a, b, c, d] = face.map(|corner| n * 8 + corner + 1);
+            writeln!(output, "f {} {} {} {}", a, b, c, d).unwrap();
+        }
+    }
+    output
+}
+
+/// The settled stack as text, with a line for each cube giving its
+/// position and the brick it belongs to.
+pub fn render_voxels(stack: &BrickStack) -> String {
+    let mut output = String::from("# x y z brick\n");
+    for (n, brick) in stack.bricks().iter().enumerate() {
+        for (x, y) in brick.footprint() {
+            for z in brick.z.clone() {
+                writeln!(output, "{} {} {} {}", x, y, z, n).unwrap();
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn obj() {
+        let stack = BrickStack::parse(EXAMPLE);
+        let obj = render_obj(&stack);
+        assert_eq!(obj.matches("\nv ").count(), 7 * 8);
+        assert_eq!(
+            obj.lines().filter(|line| line.starts_with("f ")).count(),
+            7 * 6
+        );
+        assert!(obj.starts_with("o brick0\nv 1 0 1\nv 2 0 1\nv 1 3 1\n"));
+        assert!(obj.ends_with("f 50 52 56 54\n"));
+    }
+
+    #[test]
+    fn voxels() {
+        let stack = BrickStack::parse(EXAMPLE);
+        let voxels = render_voxels(&stack);
+        let lines = voxels.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1 + 3 * 6 + 2);
+        assert_eq!(lines[1], "1 0 1 0");
+        assert_eq!(lines[lines.len() - 1], "1 1 6 6");
+    }
+}
@@ -0,0 +1,299 @@
+use crate::longest_path::LongestPath;
+use grid::Grid;
+use std::fmt::Write;
+
+/// Whether slopes can only be walked down, as in part 1, or are as good as
+/// paths, as in part 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slopes {
+    Icy,
+    Dry,
+}
+
+/// The hiking trails with every corridor collapsed into a single weighted
+/// edge between the junctions at its ends. Junctions are numbered densely
+/// in reading order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunctionGraph {
+    junctions: Vec<(usize, usize)>,
+    edges: Vec<Vec<(usize, usize)>>,
+    start: usize,
+    target: usize,
+    slopes: Slopes,
+}
+
+impl JunctionGraph {
+    /// The graph of the puzzle's map, from the gap in the top row to the gap
+    /// in the bottom row.
+    pub fn parse(input: &str, slopes: Slopes) -> Self {
+        let lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        let cols = lines[0].len();
+        let grid = Grid::from_vec(lines.into_iter().flatten().copied().collect(), cols);
+        let start = (0, 1);
+        let target = (grid.rows() - 1, grid.cols() - 2);
+        Self::compress(&grid, start, target, slopes, |cell| cell != b'#')
+    }
+
+    /// Compresses the map into a graph, walking only on cells `passable`
+    /// accepts. With icy slopes `^`, `v`, `<` and `>` can only be entered and
+    /// left in the direction they point. Junctions are the start, the target
+    /// and every cell with more than two passable neighbours.
+    pub fn compress(
+        grid: &Grid<u8>,
+        start: (usize, usize),
+        target: (usize, usize),
+        slopes: Slopes,
+        passable: impl Fn(u8) -> bool,
+    ) -> Self {
+        let passable =
+            |(row, col): (usize, usize)| grid.get(row, col).is_some_and(|&cell| passable(cell));
+        let neighbours = |(row, col): (usize, usize)| {
+            [
+                row.checked_sub(1).map(|row| (row, col)),
+                Some((row + 1, col)),
+                col.checked_sub(1).map(|col| (row, col)),
+                Some((row, col + 1)),
+            ]
+            .into_iter()
+            .flatten()
+            .filter(move |&cell| passable(cell))
+        };
+        let can_step = |from: (usize, usize), to: (usize, usize)| {
+            slopes == Slopes::Dry
+                || [from, to].into_iter().all(|cell| match grid[cell] {
+                    b'^' => to.0 < from.0,
+                    b'v' => to.0 > from.0,
+                    b'<' => to.1 < from.1,
+                    b'>' => to.1 > from.1,
+                    _ => true,
+                })
+        };
+
+        let junctions: Vec<(usize, usize)> = grid
+            .indexed_iter()
+            .map(|(cell, _)| cell)
+            .filter(|&cell| {
+                passable(cell) && (cell == start || cell == target || neighbours(cell).count() > 2)
+            })
+            .collect();
+        let index = |cell: &(usize, usize)| junctions.binary_search(cell).ok();
+
+        let edges = junctions
+            .iter()
+            .enumerate()
+            .map(|(from, &junction)| {
+                neighbours(junction)
+                    .filter_map(|first| {
+                        let (mut previous, mut cell) = (junction, first);
+                        for length in 1.. {
+                            if !can_step(previous, cell) {
+                                return None;
+                            }
+                            if let Some(to) = index(&cell) {
+                                return (to != from).then_some((to, length));
+                            }
+                            let next = neighbours(cell).find(|&next| next != previous)?;
+                            (previous, cell) = (cell, next);
+                        }
+                        unreachable!()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            start: index(&start).unwrap(),
+            target: index(&target).unwrap(),
+            junctions,
+            edges,
+            slopes,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.junctions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.junctions.is_empty()
+    }
+
+    pub fn slopes(&self) -> Slopes {
+        self.slopes
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    /// The junction's row and column on the map.
+    pub fn position(&self, junction: usize) -> (usize, usize) {
+        self.junctions[junction]
+    }
+
+    /// `(to, length)` for each corridor that can be walked out of the
+    /// junction.
+    pub fn edges(&self, junction: usize) -> &[(usize, usize)] {
+        &self.edges[junction]
+    }
+
+    /// Every corridor as `(from, to, length)`. With dry slopes each corridor
+    /// is listed once, from the lower numbered junction.
+    pub fn corridors(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, edges)| {
+                edges
+                    .iter()
+                    .filter(move |&&(to, _)| self.slopes == Slopes::Icy || from < to)
+                    .map(move |&(to, length)| (from, to, length))
+            })
+    }
+
+    pub fn solver(&self) -> Result<LongestPath, String> {
+        LongestPath::new(self.edges.clone(), self.start, self.target)
+    }
+
+    fn label(&self, junction: usize) -> String {
+        match junction {
+            junction if junction == self.start => "start".to_string(),
+            junction if junction == self.target => "target".to_string(),
+            junction => {
+                let (row, col) = self.junctions[junction];
+                format!("{},{}", row, col)
+            }
+        }
+    }
+
+    /// The graph in Graphviz DOT format, as a `digraph` with icy slopes and
+    /// a `graph` with dry ones.
+    pub fn render_dot(&self) -> String {
+        let (kind, arrow) = match self.slopes {
+            Slopes::Icy => ("digraph", "->"),
+            Slopes::Dry => ("graph", "--"),
+        };
+        let mut output = format!("{} trails {{\n", kind);
+        for junction in 0..self.len() {
+            let style = if junction == self.start || junction == self.target {
+                " style=filled"
+            } else {
+                ""
+            };
+            writeln!(
+                output,
+                r#"  node{} [label="{}"{}];"#,
+                junction,
+                self.label(junction),
+                style
+            )
+            .unwrap();
+        }
+        for (from, to, length) in self.corridors() {
+            writeln!(
+                output,
+                "  node{} {} node{} [label={}];",
+                from, arrow, to, length
+            )
+            .unwrap();
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// The graph as JSON, with junctions listed by position and corridors
+    /// as in [`corridors`](Self::corridors).
+    pub fn render_json(&self) -> String {
+        let junctions: Vec<String> = self
+            .junctions
+            .iter()
+            .map(|(row, col)| format!(r#"{{"row":{},"col":{}}}"#, row, col))
+            .collect();
+        let corridors: Vec<String> = self
+            .corridors()
+            .map(|(from, to, length)| {
+                format!(r#"{{"from":{},"to":{},"length":{}}}"#, from, to, length)
+            })
+            .collect();
+        format!(
+            r#"{{"directed":{},"start":{},"target":{},"junctions":[{}],"corridors":[{}]}}"#,
+            self.slopes == Slopes::Icy,
+            self.start,
+            self.target,
+            junctions.join(","),
+            corridors.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn dry() {
+        let graph = JunctionGraph::parse(EXAMPLE, Slopes::Dry);
+        assert_eq!(graph.len(), 9);
+        assert_eq!(graph.position(graph.start()), (0, 1));
+        assert_eq!(graph.position(graph.target()), (22, 21));
+        assert_eq!(graph.edges(graph.start()), [(2, 15)]);
+        assert_eq!(graph.corridors().count(), 12);
+        assert_eq!(graph.solver().unwrap().solve(), Some(154));
+    }
+
+    #[test]
+    fn icy() {
+        let graph = JunctionGraph::parse(EXAMPLE, Slopes::Icy);
+        assert_eq!(graph.len(), 9);
+        assert_eq!(graph.corridors().count(), 12);
+        // Slopes point away from the start, so nothing leads back to it.
+        assert!((0..graph.len()).all(|junction| graph
+            .edges(junction)
+            .iter()
+            .all(|&(to, _)| to != graph.start())));
+        assert_eq!(graph.solver().unwrap().solve(), Some(94));
+    }
+
+    #[test]
+    fn passable() {
+        let lines: Vec<&[u8]> = EXAMPLE.lines().map(str::as_bytes).collect();
+        let cols = lines[0].len();
+        let grid = Grid::from_vec(lines.into_iter().flatten().copied().collect(), cols);
+        let (start, target) = ((0, 1), (22, 21));
+        let graph = JunctionGraph::compress(&grid, start, target, Slopes::Dry, |cell| cell != b'#');
+        assert_eq!(graph, JunctionGraph::parse(EXAMPLE, Slopes::Dry));
+
+        // Every junction is ringed by slopes, so without them nothing connects.
+        let graph = JunctionGraph::compress(&grid, start, target, Slopes::Dry, |cell| cell == b'.');
+        assert_eq!(graph.len(), 2);
+        assert!(graph.edges(graph.start()).is_empty());
+    }
+
+    #[test]
+    fn render() {
+        let graph = JunctionGraph::parse(EXAMPLE, Slopes::Dry);
+        let dot = graph.render_dot();
+        assert!(dot.starts_with("graph trails {\n"));
+        assert!(dot.contains(r#"  node0 [label="start" style=filled];"#));
+        assert!(dot.contains(r#"  node2 [label="5,3"];"#));
+        assert!(dot.contains("  node0 -- node2 [label=15];"));
+        assert_eq!(dot.matches(" -- ").count(), 12);
+
+        let dot = JunctionGraph::parse(EXAMPLE, Slopes::Icy).render_dot();
+        assert!(dot.starts_with("digraph trails {\n"));
+        assert!(dot.contains("  node0 -> node2 [label=15];"));
+
+        let json = graph.render_json();
+        assert!(json.starts_with(
+            r#"{"directed":false,"start":0,"target":8,"junctions":[{"row":0,"col":1},{"row":3,"col":11},"#
+        ));
+        assert!(json.contains(r#"{"from":0,"to":2,"length":15}"#));
+        assert_eq!(json.matches(r#""length""#).count(), 12);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod junction_graph;
+pub mod longest_path;
 pub mod part1;
 pub mod part2;
 
@@ -0,0 +1,248 @@
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Most junctions a visited set can hold.
+pub const MAX_JUNCTIONS: usize = 64;
+
+/// Levels searched up front to split the remaining branches between threads.
+const PARALLEL_DEPTH: usize = 6;
+
+/// Longest simple path between two junctions of a weighted graph, found by a
+/// depth-first search with `u64` visited sets. Junctions are numbered densely
+/// from zero, and `edges[from]` lists `(to, length)` for each way out of
+/// `from`, so an undirected graph lists each edge from both ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LongestPath {
+    edges: Vec<Vec<(usize, usize)>>,
+    neighbours: Vec<u64>,
+    longest_in: Vec<usize>,
+    start: usize,
+    goal: usize,
+    bonus: usize,
+}
+
+impl LongestPath {
+    pub fn new(
+        edges: Vec<Vec<(usize, usize)>>,
+        start: usize,
+        target: usize,
+    ) -> Result<Self, String> {
+        if edges.len() > MAX_JUNCTIONS {
+            return Err(format!(
+                "Too many junctions: {} (at most {})",
+                edges.len(),
+                MAX_JUNCTIONS
+            ));
+        }
+        if let Some(junction) = [start, target]
+            .into_iter()
+            .chain(edges.iter().flatten().map(|&(to, _)| to))
+            .find(|&junction| junction >= edges.len())
+        {
+            return Err(format!("Unknown junction: {}", junction));
+        }
+
+        let neighbours = edges
+            .iter()
+            .map(|edges| edges.iter().fold(0, |mask, &(to, _)| mask | 1 << to))
+            .collect();
+        let mut longest_in = vec![0; edges.len()];
+        for &(to, length) in edges.iter().flatten() {
+            longest_in[to] = longest_in[to].max(length);
+        }
+
+        // Once the search enters the only junction leading to the target, it
+        // can't leave without cutting itself off, so stop there instead.
+        let (mut goal, mut bonus, mut chain) = (target, 0, 1u64 << target);
+        while goal != start {
+            let mut into_goal = edges.iter().enumerate().filter(|&(from, edges)| {
+                chain & 1 << from == 0 && edges.iter().any(|&(to, _)| to == goal)
+            });
+            match (into_goal.next(), into_goal.next()) {
+                (Some((from, edges)), None) => {
+                    bonus += edges
+                        .iter()
+                        .filter(|&&(to, _)| to == goal)
+                        .map(|&(_, length)| length)
+                        .max()
+                        .unwrap();
+                    goal = from;
+                    chain |= 1 << from;
+                }
+                _ => break,
+            }
+        }
+
+        Ok(Self {
+            edges,
+            neighbours,
+            longest_in,
+            start,
+            goal,
+            bonus,
+        })
+    }
+
+    /// Length of the longest path from the start to the target that visits no
+    /// junction twice, or `None` if the target can't be reached.
+    pub fn solve(&self) -> Option<usize> {
+        if self.start == self.goal {
+            return Some(self.bonus);
+        }
+
+        // Every path is at least one step long, so zero means none found yet.
+        let best = AtomicUsize::new(0);
+        let mut branches = Vec::new();
+        self.branch(self.start, 1 << self.start, 0, 0, &best, &mut branches);
+        branches
+            .into_par_iter()
+            .for_each(|(node, visited, distance)| self.search(node, visited, distance, &best));
+
+        match best.into_inner() {
+            0 => None,
+            best => Some(best + self.bonus),
+        }
+    }
+
+    /// Walks the first levels of the search, collecting the branches left to
+    /// explore.
+    fn branch(
+        &self,
+        node: usize,
+        visited: u64,
+        distance: usize,
+        depth: usize,
+        best: &AtomicUsize,
+        branches: &mut Vec<(usize, u64, usize)>,
+    ) {
+        if node == self.goal {
+            best.fetch_max(distance, Ordering::Relaxed);
+        } else if depth == PARALLEL_DEPTH {
+            branches.push((node, visited, distance));
+        } else {
+            for &(to, length) in &self.edges[node] {
+                if visited & 1 << to == 0 {
+                    let visited = visited | 1 << to;
+                    self.branch(to, visited, distance + length, depth + 1, best, branches);
+                }
+            }
+        }
+    }
+
+    fn search(&self, node: usize, visited: u64, distance: usize, best: &AtomicUsize) {
+        if node == self.goal {
+            best.fetch_max(distance, Ordering::Relaxed);
+            return;
+        }
+
+        // The rest of the path enters each junction it visits once, so it's
+        // no longer than the longest way into each junction still reachable.
+        let reachable = self.reachable(node, visited);
+        if reachable & 1 << self.goal == 0 {
+            return;
+        }
+        let bound = distance + ones(reachable).map(|n| self.longest_in[n]).sum::<usize>();
+        if bound <= best.load(Ordering::Relaxed) {
+            return;
+        }
+
+        for &(to, length) in &self.edges[node] {
+            if visited & 1 << to == 0 {
+                self.search(to, visited | 1 << to, distance + length, best);
+            }
+        }
+    }
+
+    /// Junctions reachable from `node` without passing through a visited
+    /// junction or the goal.
+    fn reachable(&self, node: usize, visited: u64) -> u64 {
+        let mut reachable = 0;
+        let mut frontier = self.neighbours[node] & !visited;
+        while frontier != 0 {
+            reachable |= frontier;
+            frontier = ones(frontier & !(1 << self.goal))
+                .fold(0, |next, n| next | self.neighbours[n])
+                & !visited
+                & !reachable;
+        }
+        reachable
+    }
+}
+
+/// Indices of the set bits of `mask`, lowest first.
+fn ones(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (mask != 0).then(|| {
+            let n = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            n
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn undirected(edges: &[(usize, usize, usize)], junctions: usize) -> Vec<Vec<(usize, usize)>> {
+        let mut adjacency = vec![Vec::new(); junctions];
+        for &(a, b, length) in edges {
+            adjacency[a].push((b, length));
+            adjacency[b].push((a, length));
+        }
+        adjacency
+    }
+
+    #[test]
+    fn square() {
+        // 0 - 1 - 3 - 4 with a detour through 2 between 1 and 3.
+        let edges = undirected(&[(0, 1, 1), (1, 3, 5), (1, 2, 3), (2, 3, 4), (3, 4, 2)], 5);
+        let solver = LongestPath::new(edges, 0, 4).unwrap();
+        assert_eq!(solver.solve(), Some(10));
+        assert_eq!(solver.goal, 3);
+        assert_eq!(solver.bonus, 2);
+    }
+
+    #[test]
+    fn directed() {
+        let edges = vec![vec![(1, 2), (2, 7)], vec![(2, 3)], vec![(3, 1)], vec![]];
+        assert_eq!(
+            LongestPath::new(edges.clone(), 0, 3).unwrap().solve(),
+            Some(8)
+        );
+        assert_eq!(LongestPath::new(edges.clone(), 3, 0).unwrap().solve(), None);
+        assert_eq!(LongestPath::new(edges, 2, 2).unwrap().solve(), Some(0));
+    }
+
+    #[test]
+    fn lattice() {
+        // A 6 by 6 lattice like the puzzle's, where the longest path must
+        // snake through all but one junction.
+        let mut edges = Vec::new();
+        for row in 0..6 {
+            for col in 0..6 {
+                let n = row * 6 + col;
+                if col < 5 {
+                    edges.push((n, n + 1, 1));
+                }
+                if row < 5 {
+                    edges.push((n, n + 6, 1));
+                }
+            }
+        }
+        let solver = LongestPath::new(undirected(&edges, 36), 0, 35).unwrap();
+        assert_eq!(solver.solve(), Some(34));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            LongestPath::new(vec![Vec::new(); 65], 0, 1),
+            Err("Too many junctions: 65 (at most 64)".to_string())
+        );
+        assert_eq!(
+            LongestPath::new(vec![vec![(2, 1)], vec![]], 0, 1),
+            Err("Unknown junction: 2".to_string())
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use num::rational::Ratio;
+use num::Zero;
+
+/// Exact fraction, large enough for positions around 4e14 times velocities.
+pub type Rational = Ratio<i128>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hailstone {
+    pub px: i64,
+    pub py: i64,
+    pub pz: i64,
+    pub vx: i64,
+    pub vy: i64,
+    pub vz: i64,
+}
+
+/// How the paths of two hailstones meet in the x and y axes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Crossing {
+    /// The paths cross at a single point, which the first hailstone reaches
+    /// at `t1` and the second at `t2`. Negative times are in the past.
+    Point {
+        x: Rational,
+        y: Rational,
+        t1: Rational,
+        t2: Rational,
+    },
+    Parallel,
+    /// Both hailstones move along the same line.
+    Collinear,
+}
+
+impl Crossing {
+    /// Whether both hailstones reach a single crossing point now or later.
+    pub fn is_in_future(&self) -> bool {
+        match self {
+            Crossing::Point { t1, t2, .. } => *t1 >= Rational::zero() && *t2 >= Rational::zero(),
+            Crossing::Parallel | Crossing::Collinear => false,
+        }
+    }
+}
+
+impl Hailstone {
+    pub fn parse(line: &str) -> Self {
+        let (position, velocity) = line.split_once(" @ ").unwrap();
+        let mut position = position.split(',').map(str::trim).map(str::parse);
+        let px = position.next().unwrap().unwrap();
+        let py = position.next().unwrap().unwrap();
+        let pz = position.next().unwrap().unwrap();
+        let mut velocity = velocity.split(',').map(str::trim).map(str::parse);
+        let vx = velocity.next().unwrap().unwrap();
+        let vy = velocity.next().unwrap().unwrap();
+        let vz = velocity.next().unwrap().unwrap();
+        Self {
+            px,
+            py,
+            pz,
+            vx,
+            vy,
+            vz,
+        }
+    }
+
+    pub fn parse_all(input: &str) -> Vec<Self> {
+        input.lines().map(Self::parse).collect()
+    }
+
+    pub fn position_at(&self, t: Rational) -> [Rational; 3] {
+        [(self.px, self.vx), (self.py, self.vy), (self.pz, self.vz)]
+            .map(|(p, v)| Rational::from(p as i128) + t * v as i128)
+    }
+
+    /// Where the paths of the two hailstones cross, ignoring the z axis.
+    pub fn crossing_xy(&self, other: &Self) -> Crossing {
+        let (dx, dy) = (
+            other.px as i128 - self.px as i128,
+            other.py as i128 - self.py as i128,
+        );
+        let (ax, ay) = (self.vx as i128, self.vy as i128);
+        let (bx, by) = (other.vx as i128, other.vy as i128);
+
+        let det = ax * by - ay * bx;
+        if det == 0 {
+            let on_line = |vx: i128, vy: i128| dx * vy - dy * vx == 0;
+            let moving = (ax, ay) != (0, 0) || (bx, by) != (0, 0);
+            return if on_line(ax, ay) && on_line(bx, by) && (moving || (dx, dy) == (0, 0)) {
+                Crossing::Collinear
+            } else {
+                Crossing::Parallel
+            };
+        }
+
+        let t1 = Rational::new(dx * by - dy * bx, det);
+        let t2 = Rational::new(dx * ay - dy * ax, det);
+        Crossing::Point {
+            x: Rational::from(self.px as i128) + t1 * ax,
+            y: Rational::from(self.py as i128) + t1 * ay,
+            t1,
+            t2,
+        }
+    }
+
+    /// Whether the future paths of two collinear hailstones share a point
+    /// with x and y between `min` and `max`.
+    pub fn overlap_xy_in_area(&self, other: &Self, min: i128, max: i128) -> bool {
+        let (moving, other) = if (self.vx, self.vy) == (0, 0) {
+            (other, self)
+        } else {
+            (self, other)
+        };
+        let in_area = |p: i128| (min..=max).contains(&p);
+        if (moving.vx, moving.vy) == (0, 0) {
+            return moving.px == other.px
+                && moving.py == other.py
+                && in_area(moving.px as i128)
+                && in_area(moving.py as i128);
+        }
+
+        // Times along the moving hailstone's path, from `low` to `high` with
+        // `None` for no limit.
+        let mut low = Rational::zero();
+        let mut high: Option<Rational> = None;
+        let mut limit = |from: Option<Rational>, to: Option<Rational>| {
+            if let Some(from) = from {
+                low = low.max(from);
+            }
+            if let Some(to) = to {
+                high = Some(high.map_or(to, |high| high.min(to)));
+            }
+        };
+
+        let (p, v, other_p, other_v) = if moving.vx != 0 {
+            (moving.px, moving.vx, other.px, other.vx)
+        } else {
+            (moving.py, moving.vy, other.py, other.vy)
+        };
+        let meets_other = Rational::new(other_p as i128 - p as i128, v as i128);
+        match (other_v as i128 * v as i128).signum() {
+            1 => limit(Some(meets_other), None),
+            -1 => limit(None, Some(meets_other)),
+            _ => limit(Some(meets_other), Some(meets_other)),
+        }
+
+        for (p, v) in [(moving.px, moving.vx), (moving.py, moving.vy)] {
+            let (p, v) = (p as i128, v as i128);
+            if v == 0 {
+                if !in_area(p) {
+                    return false;
+                }
+            } else {
+                let (a, b) = (Rational::new(min - p, v), Rational::new(max - p, v));
+                limit(Some(a.min(b)), Some(a.max(b)));
+            }
+        }
+
+        high.is_none_or(|high| low <= high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hailstone(line: &str) -> Hailstone {
+        Hailstone::parse(line)
+    }
+
+    #[test]
+    fn point() {
+        let a = hailstone("19, 13, 30 @ -2, 1, -2");
+        let b = hailstone("18, 19, 22 @ -1, -1, -2");
+        let crossing = a.crossing_xy(&b);
+        assert_eq!(
+            crossing,
+            Crossing::Point {
+                x: Rational::new(43, 3),
+                y: Rational::new(46, 3),
+                t1: Rational::new(7, 3),
+                t2: Rational::new(11, 3),
+            }
+        );
+        assert!(crossing.is_in_future());
+        assert_eq!(
+            &a.position_at(Rational::new(7, 3))[..2],
+            [Rational::new(43, 3), Rational::new(46, 3)]
+        );
+
+        // Crossed in the past for the second hailstone only.
+        let c = hailstone("20, 19, 15 @ 1, -5, -3");
+        assert!(!a.crossing_xy(&c).is_in_future());
+    }
+
+    #[test]
+    fn large() {
+        // Nearly parallel paths far from the origin, where f64 loses the
+        // crossing point.
+        let a = hailstone("400000000000000, 300000000000000, 0 @ -997, 999, 0");
+        let b = hailstone("200000000000001, 200000000000000, 0 @ 998, -1000, 0");
+        let Crossing::Point { x, y, t1, t2 } = a.crossing_xy(&b) else {
+            panic!("Expected a crossing point");
+        };
+        assert_eq!(a.position_at(t1)[..2], [x, y]);
+        assert_eq!(b.position_at(t2)[..2], [x, y]);
+    }
+
+    #[test]
+    fn parallel_and_collinear() {
+        let a = hailstone("0, 0, 0 @ 1, 1, 0");
+        assert_eq!(
+            a.crossing_xy(&hailstone("0, 1, 0 @ 2, 2, 0")),
+            Crossing::Parallel
+        );
+
+        let ahead = hailstone("5, 5, 0 @ 2, 2, 0");
+        assert_eq!(a.crossing_xy(&ahead), Crossing::Collinear);
+        assert!(a.overlap_xy_in_area(&ahead, 10, 20));
+        assert!(!a.overlap_xy_in_area(&ahead, -5, -1));
+
+        // Moving apart, so the future paths never meet.
+        let behind = hailstone("-5, -5, 0 @ -1, -1, 0");
+        assert!(!a.overlap_xy_in_area(&behind, -4, 20));
+        // Moving towards each other.
+        let opposite = hailstone("8, 8, 0 @ -1, -1, 0");
+        assert!(a.overlap_xy_in_area(&opposite, 7, 9));
+        assert!(!a.overlap_xy_in_area(&opposite, 9, 12));
+    }
+}
@@ -1,5 +1,9 @@
+pub mod hailstone;
+pub mod linear_algebra;
 pub mod part1;
 pub mod part2;
+pub mod rock;
+pub mod timeline;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
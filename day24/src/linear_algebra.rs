@@ -0,0 +1,275 @@
+use num::{BigInt, BigRational, One, Zero};
+use std::fmt;
+
+/// Why a linear system has no unique solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// No assignment satisfies every equation.
+    Inconsistent,
+    /// Too few independent equations to pin down every unknown.
+    Underdetermined { rank: usize, unknowns: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Inconsistent => write!(f, "the equations are inconsistent"),
+            Error::Underdetermined { rank, unknowns } => write!(
+                f,
+                "only {} independent equations for {} unknowns",
+                rank, unknowns
+            ),
+        }
+    }
+}
+
+/// An integer matrix, stored by rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    cols: usize,
+    rows: Vec<Vec<BigInt>>,
+}
+
+impl Matrix {
+    pub fn new(cols: usize) -> Self {
+        Self {
+            cols,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn from_rows(cols: usize, rows: impl IntoIterator<Item = Vec<BigInt>>) -> Self {
+        let mut matrix = Self::new(cols);
+        for row in rows {
+            matrix.push_row(row);
+        }
+        matrix
+    }
+
+    pub fn push_row(&mut self, row: Vec<BigInt>) {
+        assert_eq!(row.len(), self.cols, "Row has the wrong length");
+        self.rows.push(row);
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, row: usize) -> &[BigInt] {
+        &self.rows[row]
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_rows(
+            self.rows(),
+            (0..self.cols).map(|col| self.rows.iter().map(|row| row[col].clone()).collect()),
+        )
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        assert_eq!(self.cols, other.rows(), "Matrix sizes don't match");
+        Self::from_rows(
+            other.cols,
+            self.rows.iter().map(|row| {
+                (0..other.cols)
+                    .map(|col| {
+                        row.iter()
+                            .zip(&other.rows)
+                            .map(|(a, other_row)| a * &other_row[col])
+                            .sum()
+                    })
+                    .collect()
+            }),
+        )
+    }
+
+    pub fn rank(&self) -> usize {
+        self.echelon().1.len()
+    }
+
+    /// Row echelon form by fraction-free (Bareiss) elimination, which keeps
+    /// every entry an integer, and the column of each row's pivot.
+    pub fn echelon(&self) -> (Self, Vec<usize>) {
+        let mut rows = self.rows.clone();
+        let mut pivots = Vec::new();
+        let mut previous = BigInt::one();
+        for col in 0..self.cols {
+            let r = pivots.len();
+            let Some(pivot) = (r..rows.len()).find(|&row| !rows[row][col].is_zero()) else {
+                continue;
+            };
+            rows.swap(r, pivot);
+            let (above, below) = rows.split_at_mut(r + 1);
+            let pivot_row = &above[r];
+            for row in below {
+                for j in col + 1..self.cols {
+                    row[j] = (&pivot_row[col] * &row[j] - &row[col] * &pivot_row[j]) / &previous;
+                }
+                row[col] = BigInt::zero();
+            }
+            previous = pivot_row[col].clone();
+            pivots.push(col);
+        }
+        (
+            Self {
+                cols: self.cols,
+                rows,
+            },
+            pivots,
+        )
+    }
+
+    /// The unique `x` with `self * x = rhs`.
+    pub fn solve(&self, rhs: &[BigInt]) -> Result<Vec<BigRational>, Error> {
+        assert_eq!(
+            rhs.len(),
+            self.rows(),
+            "Right-hand side has the wrong length"
+        );
+        let augmented = Self::from_rows(
+            self.cols + 1,
+            self.rows
+                .iter()
+                .zip(rhs)
+                .map(|(row, b)| row.iter().chain([b]).cloned().collect()),
+        );
+        let (echelon, pivots) = augmented.echelon();
+        if pivots.last() == Some(&self.cols) {
+            return Err(Error::Inconsistent);
+        }
+        if pivots.len() < self.cols {
+            return Err(Error::Underdetermined {
+                rank: pivots.len(),
+                unknowns: self.cols,
+            });
+        }
+
+        // With full rank, row `i` has its pivot in column `i`.
+        let mut x = vec![BigRational::zero(); self.cols];
+        for i in (0..self.cols).rev() {
+            let row = echelon.row(i);
+            let rest: BigRational = (i + 1..self.cols)
+                .map(|j| &x[j] * BigRational::from(row[j].clone()))
+                .sum();
+            x[i] = (BigRational::from(row[self.cols].clone()) - rest)
+                / BigRational::from(row[i].clone());
+        }
+        Ok(x)
+    }
+
+    /// `self * x - rhs` for each row.
+    pub fn residual(&self, x: &[BigRational], rhs: &[BigInt]) -> Vec<BigRational> {
+        self.rows
+            .iter()
+            .zip(rhs)
+            .map(|(row, b)| {
+                row.iter()
+                    .zip(x)
+                    .map(|(a, x)| BigRational::from(a.clone()) * x)
+                    .sum::<BigRational>()
+                    - BigRational::from(b.clone())
+            })
+            .collect()
+    }
+
+    /// The `x` minimising the squared residual, found exactly from the
+    /// normal equations, with that squared residual. It's zero exactly when
+    /// the system is consistent.
+    pub fn least_squares(&self, rhs: &[BigInt]) -> Result<(Vec<BigRational>, BigRational), Error> {
+        let transpose = self.transpose();
+        let rhs_matrix = Self::from_rows(1, rhs.iter().map(|b| vec![b.clone()]));
+        let normal_rhs: Vec<BigInt> = transpose
+            .mul(&rhs_matrix)
+            .rows
+            .into_iter()
+            .map(|mut row| row.remove(0))
+            .collect();
+        let x = transpose.mul(self).solve(&normal_rhs)?;
+        let squared = self.residual(&x, rhs).into_iter().map(|r| &r * &r).sum();
+        Ok((x, squared))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[&[i64]]) -> Matrix {
+        Matrix::from_rows(
+            rows[0].len(),
+            rows.iter()
+                .map(|row| row.iter().map(|&a| BigInt::from(a)).collect()),
+        )
+    }
+
+    fn ints(values: &[i64]) -> Vec<BigInt> {
+        values.iter().map(|&v| BigInt::from(v)).collect()
+    }
+
+    fn rational(numer: i64, denom: i64) -> BigRational {
+        BigRational::new(numer.into(), denom.into())
+    }
+
+    #[test]
+    fn solve() {
+        let a = matrix(&[&[2, 1, -1], &[-3, -1, 2], &[-2, 1, 2]]);
+        assert_eq!(a.rank(), 3);
+        let x = a.solve(&ints(&[8, -11, -3])).unwrap();
+        assert_eq!(x, [rational(2, 1), rational(3, 1), rational(-1, 1)]);
+
+        let a = matrix(&[&[0, 2], &[3, 0]]);
+        assert_eq!(
+            a.solve(&ints(&[1, 1])).unwrap(),
+            [rational(1, 3), rational(1, 2)]
+        );
+    }
+
+    #[test]
+    fn echelon() {
+        let a = matrix(&[&[1, 2, 3], &[2, 4, 7], &[3, 6, 10]]);
+        let (echelon, pivots) = a.echelon();
+        assert_eq!(pivots, [0, 2]);
+        assert_eq!(echelon.row(1), ints(&[0, 0, 1]));
+        assert!(echelon.row(2).iter().all(Zero::is_zero));
+        assert_eq!(a.rank(), 2);
+    }
+
+    #[test]
+    fn no_unique_solution() {
+        let a = matrix(&[&[1, 1], &[2, 2]]);
+        assert_eq!(a.solve(&ints(&[1, 3])), Err(Error::Inconsistent));
+        assert_eq!(
+            a.solve(&ints(&[1, 2])),
+            Err(Error::Underdetermined {
+                rank: 1,
+                unknowns: 2
+            })
+        );
+        assert_eq!(
+            Error::Underdetermined {
+                rank: 1,
+                unknowns: 2
+            }
+            .to_string(),
+            "only 1 independent equations for 2 unknowns"
+        );
+    }
+
+    #[test]
+    fn least_squares() {
+        // Overdetermined but consistent.
+        let a = matrix(&[&[1, 0], &[0, 1], &[1, 1]]);
+        let (x, squared) = a.least_squares(&ints(&[1, 2, 3])).unwrap();
+        assert_eq!(x, [rational(1, 1), rational(2, 1)]);
+        assert!(squared.is_zero());
+
+        // The best fit misses every equation by a third.
+        let (x, squared) = a.least_squares(&ints(&[1, 2, 4])).unwrap();
+        assert_eq!(x, [rational(4, 3), rational(7, 3)]);
+        assert_eq!(squared, rational(1, 3));
+    }
+}
@@ -1,85 +1,32 @@
+use crate::hailstone::{Crossing, Hailstone, Rational};
 use itertools::Itertools;
 
 pub fn solve(input: &str) -> usize {
-    count_collisions(input, 200000000000000.0, 400000000000000.0)
+    count_collisions(input, 200000000000000, 400000000000000)
 }
 
-fn count_collisions(input: &str, min: f64, max: f64) -> usize {
-    input
-        .lines()
-        .map(Hailstone::parse)
-        .map(IntersectionCalculator::new)
-        .collect::<Vec<_>>()
-        .into_iter()
-        .tuple_combinations()
-        .filter_map(|(a, b)| {
-            a.intersection(&b)
-                .filter(|intersection| a.hailstone.is_in_future(*intersection))
-                .filter(|intersection| b.hailstone.is_in_future(*intersection))
-        })
-        .filter(|(x, y)| *x >= min && *x <= max && *y >= min && *y <= max)
-        .count()
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Hailstone {
-    px: i64,
-    py: i64,
-    vx: i64,
-    vy: i64,
-}
-
-impl Hailstone {
-    fn parse(line: &str) -> Self {
-        let (position, velocity) = line.split_once(" @ ").unwrap();
-        let mut position = position.split(',').map(str::trim).map(str::parse);
-        let px = position.next().unwrap().unwrap();
-        let py = position.next().unwrap().unwrap();
-        let mut velocity = velocity.split(',').map(str::trim).map(str::parse);
-        let vx = velocity.next().unwrap().unwrap();
-        let vy = velocity.next().unwrap().unwrap();
-        Self { px, py, vx, vy }
-    }
-
-    fn is_in_future(&self, intersection: (f64, f64)) -> bool {
-        let (x, _y) = intersection;
-        if self.vx < 0 {
-            (self.px as f64) > x
-        } else {
-            (self.px as f64) < x
-        }
-    }
+fn count_collisions(input: &str, min: i128, max: i128) -> usize {
+    collisions(&Hailstone::parse_all(input), min, max).len()
 }
 
-#[derive(Debug, Clone, Copy)]
-struct IntersectionCalculator {
-    hailstone: Hailstone,
-    // (x - x') = (px - (px + vx)) = -vx
-    a: f64,
-    // (y - y') = (py - (py + vy)) = -vy
-    b: f64,
-    // (x*y' - y*x') = px*vy - py*vx
-    c: f64,
-}
-
-impl IntersectionCalculator {
-    fn new(hailstone: Hailstone) -> Self {
-        let a = hailstone.vx as f64 * -1.0;
-        let b = hailstone.vy as f64 * -1.0;
-        let c =
-            hailstone.px as f64 * hailstone.vy as f64 - hailstone.py as f64 * hailstone.vx as f64;
-        Self { hailstone, a, b, c }
-    }
-
-    fn intersection(&self, other: &Self) -> Option<(f64, f64)> {
-        let det = self.a * other.b - other.a * self.b;
-        if det == 0.0 {
-            return None;
-        }
-        let x = (self.c * other.a - self.a * other.c) / det;
-        let y = (self.c * other.b - self.b * other.c) / det;
-        Some((x, y))
-    }
+/// Every pair of hailstones, by index, whose future paths cross with x and y
+/// between `min` and `max`, and how they cross.
+pub fn collisions(hailstones: &[Hailstone], min: i128, max: i128) -> Vec<(usize, usize, Crossing)> {
+    let in_area = |p: &Rational| *p >= Rational::from(min) && *p <= Rational::from(max);
+    hailstones
+        .iter()
+        .enumerate()
+        .tuple_combinations()
+        .filter_map(|((i, a), (j, b))| {
+            let crossing = a.crossing_xy(b);
+            let collides = match &crossing {
+                Crossing::Point { x, y, .. } => crossing.is_in_future() && in_area(x) && in_area(y),
+                Crossing::Parallel => false,
+                Crossing::Collinear => a.overlap_xy_in_area(b, min, max),
+            };
+            collides.then_some((i, j, crossing))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -90,10 +37,30 @@ mod tests {
 
     #[test]
     fn example() {
-        let result = count_collisions(EXAMPLE, 7.0, 27.0);
+        let result = count_collisions(EXAMPLE, 7, 27);
         assert_eq!(result, 2);
     }
 
+    #[test]
+    fn example_collisions() {
+        let hailstones = Hailstone::parse_all(EXAMPLE);
+        let collisions = collisions(&hailstones, 7, 27);
+        assert_eq!(
+            collisions[0],
+            (
+                0,
+                1,
+                Crossing::Point {
+                    x: Rational::new(43, 3),
+                    y: Rational::new(46, 3),
+                    t1: Rational::new(7, 3),
+                    t2: Rational::new(11, 3),
+                }
+            )
+        );
+        assert_eq!((collisions[1].0, collisions[1].1), (0, 2));
+    }
+
     #[cfg(input_txt)]
     #[cfg(part1_txt)]
     #[test]
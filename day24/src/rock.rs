@@ -0,0 +1,203 @@
+use crate::hailstone::Hailstone;
+use crate::linear_algebra::{self, Matrix};
+use num::{BigInt, BigRational, Zero};
+use std::fmt;
+
+/// Unknowns: the rock's position and velocity.
+const UNKNOWNS: usize = 6;
+
+/// Why no single rock trajectory hits every hailstone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The hailstones fit no single trajectory.
+    Inconsistent,
+    /// Even every hailstone together leaves more than one trajectory.
+    Underdetermined { rank: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Inconsistent => write!(f, "no single trajectory fits every hailstone"),
+            Error::Underdetermined { rank } => write!(
+                f,
+                "the hailstones give {} of the {} independent equations needed",
+                rank, UNKNOWNS
+            ),
+        }
+    }
+}
+
+impl From<linear_algebra::Error> for Error {
+    fn from(error: linear_algebra::Error) -> Self {
+        match error {
+            linear_algebra::Error::Inconsistent => Error::Inconsistent,
+            linear_algebra::Error::Underdetermined { rank, .. } => Error::Underdetermined { rank },
+        }
+    }
+}
+
+/// The trajectory of a rock that hits every hailstone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rock {
+    pub position: [BigRational; 3],
+    pub velocity: [BigRational; 3],
+    /// How many hailstones, from the first, pinned the trajectory down.
+    pub hailstones_used: usize,
+}
+
+impl Rock {
+    pub fn position_at(&self, time: &BigRational) -> [BigRational; 3] {
+        [0, 1, 2].map(|axis| &self.position[axis] + &self.velocity[axis] * time)
+    }
+
+    /// When the rock hits the hailstone, if ever. A hailstone moving along
+    /// with the rock counts as hit when the rock is thrown.
+    pub fn hit_time(&self, hailstone: &Hailstone) -> Option<BigRational> {
+        let hailstone_position = [hailstone.px, hailstone.py, hailstone.pz];
+        let hailstone_velocity = [hailstone.vx, hailstone.vy, hailstone.vz];
+        let mut time = None;
+        for axis in 0..3 {
+            let (p, v) = (&self.position[axis], &self.velocity[axis]);
+            let hailstone_p = BigRational::from(BigInt::from(hailstone_position[axis]));
+            let hailstone_v = BigRational::from(BigInt::from(hailstone_velocity[axis]));
+            if *v == hailstone_v {
+                if *p != hailstone_p {
+                    return None;
+                }
+            } else {
+                let t = (hailstone_p - p) / (v - hailstone_v);
+                if *time.get_or_insert_with(|| t.clone()) != t {
+                    return None;
+                }
+            }
+        }
+        let time = time.unwrap_or_else(BigRational::zero);
+        (time >= BigRational::zero()).then_some(time)
+    }
+}
+
+/// The three equations, linear in the rock's position `p` and velocity `v`,
+/// from `(p - p_i) x (v - v_i) = 0` for hailstone `a` minus the same for
+/// hailstone `b`, which cancels the `p x v` term.
+fn equations(a: &Hailstone, b: &Hailstone) -> [(Vec<BigInt>, BigInt); 3] {
+    let [apx, apy, apz, avx, avy, avz] = [a.px, a.py, a.pz, a.vx, a.vy, a.vz].map(BigInt::from);
+    let [bpx, bpy, bpz, bvx, bvy, bvz] = [b.px, b.py, b.pz, b.vx, b.vy, b.vz].map(BigInt::from);
+    // p x (vb - va) + (pb - pa) x v = pb x vb - pa x va
+    let [wx, wy, wz] = [&bvx - &avx, &bvy - &avy, &bvz - &avz];
+    let [dx, dy, dz] = [&bpx - &apx, &bpy - &apy, &bpz - &apz];
+    let zero = BigInt::zero;
+    [
+        (
+            vec![zero(), wz.clone(), -&wy, zero(), -&dz, dy.clone()],
+            &bpy * &bvz - &bpz * &bvy - (&apy * &avz - &apz * &avy),
+        ),
+        (
+            vec![-&wz, zero(), wx.clone(), dz, zero(), -&dx],
+            &bpz * &bvx - &bpx * &bvz - (&apz * &avx - &apx * &avz),
+        ),
+        (
+            vec![wy, -&wx, zero(), -&dy, dx, zero()],
+            &bpx * &bvy - &bpy * &bvx - (&apx * &avy - &apy * &avx),
+        ),
+    ]
+}
+
+/// Finds the rock's trajectory from as few hailstones as possible, then
+/// checks by least squares that it fits all the others too, and that it hits
+/// each of them after it is thrown.
+pub fn throw(hailstones: &[Hailstone]) -> Result<Rock, Error> {
+    let Some((first, rest)) = hailstones.split_first() else {
+        return Err(Error::Underdetermined { rank: 0 });
+    };
+
+    let mut matrix = Matrix::new(UNKNOWNS);
+    let mut rhs = Vec::new();
+    let mut solution = None;
+    for (used, hailstone) in rest.iter().enumerate() {
+        for (row, b) in equations(first, hailstone) {
+            matrix.push_row(row);
+            rhs.push(b);
+        }
+        if solution.is_none() && matrix.rank() == UNKNOWNS {
+            solution = Some((matrix.solve(&rhs)?, used + 2));
+        }
+    }
+    let Some((x, hailstones_used)) = solution else {
+        return Err(Error::Underdetermined {
+            rank: matrix.rank(),
+        });
+    };
+
+    let (_, squared) = matrix.least_squares(&rhs)?;
+    if !squared.is_zero() {
+        return Err(Error::Inconsistent);
+    }
+
+    let mut x = x.into_iter();
+    let rock = Rock {
+        position: [(); 3].map(|_| x.next().unwrap()),
+        velocity: [(); 3].map(|_| x.next().unwrap()),
+        hailstones_used,
+    };
+    if hailstones
+        .iter()
+        .any(|hailstone| rock.hit_time(hailstone).is_none())
+    {
+        return Err(Error::Inconsistent);
+    }
+    Ok(rock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    fn ints(values: [i64; 3]) -> [BigRational; 3] {
+        values.map(|v| BigRational::from(BigInt::from(v)))
+    }
+
+    #[test]
+    fn example() {
+        let rock = throw(&Hailstone::parse_all(EXAMPLE)).unwrap();
+        assert_eq!(rock.position, ints([24, 13, 10]));
+        assert_eq!(rock.velocity, ints([-3, 1, 2]));
+        assert_eq!(rock.hailstones_used, 3);
+        let hailstone = Hailstone::parse("19, 13, 30 @ -2, 1, -2");
+        let time = rock.hit_time(&hailstone).unwrap();
+        assert_eq!(time, BigRational::from(BigInt::from(5)));
+        assert_eq!(rock.position_at(&time), ints([9, 18, 20]));
+        assert_eq!(
+            rock.hit_time(&Hailstone::parse("19, 13, 30 @ -2, 1, -1")),
+            None
+        );
+    }
+
+    #[test]
+    fn inconsistent() {
+        let mut hailstones = Hailstone::parse_all(EXAMPLE);
+        hailstones[4].px += 1;
+        assert_eq!(throw(&hailstones), Err(Error::Inconsistent));
+
+        // On the rock's path, but two nanoseconds before it is thrown.
+        let mut hailstones = Hailstone::parse_all(EXAMPLE);
+        hailstones.push(Hailstone::parse("32, 11, 6 @ 1, 0, 0"));
+        assert_eq!(throw(&hailstones), Err(Error::Inconsistent));
+    }
+
+    #[test]
+    fn underdetermined() {
+        let hailstones = Hailstone::parse_all(EXAMPLE);
+        assert_eq!(
+            throw(&hailstones[..2]),
+            Err(Error::Underdetermined { rank: 3 })
+        );
+        assert_eq!(throw(&[]), Err(Error::Underdetermined { rank: 0 }));
+        assert_eq!(
+            Error::Underdetermined { rank: 3 }.to_string(),
+            "the hailstones give 3 of the 6 independent equations needed"
+        );
+    }
+}
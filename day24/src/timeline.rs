@@ -0,0 +1,145 @@
+use crate::hailstone::Hailstone;
+use crate::rock::Rock;
+use num::BigRational;
+use std::fmt::{self, Write};
+
+/// The rock hitting one hailstone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    /// Index of the hailstone in the input.
+    pub hailstone: usize,
+    pub time: BigRational,
+    pub position: [BigRational; 3],
+}
+
+/// A hailstone the rock never hits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Missed {
+    pub hailstone: usize,
+}
+
+impl fmt::Display for Missed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the rock misses hailstone {}", self.hailstone)
+    }
+}
+
+/// Every collision in the order they happen, or the first hailstone the
+/// rock misses.
+pub fn timeline(rock: &Rock, hailstones: &[Hailstone]) -> Result<Vec<Collision>, Missed> {
+    let mut collisions = hailstones
+        .iter()
+        .enumerate()
+        .map(|(hailstone, h)| {
+            let time = rock.hit_time(h).ok_or(Missed { hailstone })?;
+            Ok(Collision {
+                hailstone,
+                position: rock.position_at(&time),
+                time,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    collisions.sort_by(|a, b| (&a.time, a.hailstone).cmp(&(&b.time, b.hailstone)));
+    Ok(collisions)
+}
+
+/// One line per collision, with fractions written as `n/d`.
+pub fn render_csv(collisions: &[Collision]) -> String {
+    let mut output = String::from("time,hailstone,x,y,z\n");
+    for collision in collisions {
+        let [x, y, z] = &collision.position;
+        writeln!(
+            output,
+            "{},{},{},{},{}",
+            collision.time, collision.hailstone, x, y, z
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// The collisions as a JSON array. Whole numbers are written as numbers and
+/// fractions as `"n/d"` strings, so nothing is rounded.
+pub fn render_json(collisions: &[Collision]) -> String {
+    let number = |n: &BigRational| {
+        if n.is_integer() {
+            n.to_string()
+        } else {
+            format!(r#""{}""#, n)
+        }
+    };
+    let collisions: Vec<String> = collisions
+        .iter()
+        .map(|collision| {
+            let [x, y, z] = &collision.position;
+            format!(
+                r#"{{"time":{},"hailstone":{},"x":{},"y":{},"z":{}}}"#,
+                number(&collision.time),
+                collision.hailstone,
+                number(x),
+                number(y),
+                number(z)
+            )
+        })
+        .collect();
+    format!("[{}]", collisions.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rock::throw;
+    use num::BigInt;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let hailstones = Hailstone::parse_all(EXAMPLE);
+        let rock = throw(&hailstones).unwrap();
+        let collisions = timeline(&rock, &hailstones).unwrap();
+        assert_eq!(
+            collisions
+                .iter()
+                .map(|collision| collision.hailstone)
+                .collect::<Vec<_>>(),
+            [4, 1, 2, 0, 3]
+        );
+        assert_eq!(
+            render_csv(&collisions),
+            "time,hailstone,x,y,z\n\
+             1,4,21,14,12\n\
+             3,1,15,16,16\n\
+             4,2,12,17,18\n\
+             5,0,9,18,20\n\
+             6,3,6,19,22\n"
+        );
+        assert!(render_json(&collisions)
+            .starts_with(r#"[{"time":1,"hailstone":4,"x":21,"y":14,"z":12},"#));
+    }
+
+    #[test]
+    fn missed() {
+        let mut hailstones = Hailstone::parse_all(EXAMPLE);
+        let rock = throw(&hailstones).unwrap();
+        hailstones.push(Hailstone::parse("0, 0, 0 @ 1, 1, 1"));
+        let missed = timeline(&rock, &hailstones).unwrap_err();
+        assert_eq!(missed, Missed { hailstone: 5 });
+        assert_eq!(missed.to_string(), "the rock misses hailstone 5");
+    }
+
+    #[test]
+    fn fractions() {
+        let collision = Collision {
+            hailstone: 0,
+            time: BigRational::new(BigInt::from(1), BigInt::from(2)),
+            position: [1, 2, 3].map(|n| BigRational::from(BigInt::from(n))),
+        };
+        let collisions = [collision];
+        assert_eq!(render_csv(&collisions).lines().nth(1), Some("1/2,0,1,2,3"));
+        assert_eq!(
+            render_json(&collisions),
+            r#"[{"time":"1/2","hailstone":0,"x":1,"y":2,"z":3}]"#
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod min_cut;
 pub mod part1;
 pub mod part2;
 
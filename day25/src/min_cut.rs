@@ -0,0 +1,258 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Why the components can't be split by the requested number of wires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    TooFewComponents,
+    /// Every cut needs more wires than this.
+    NoCut {
+        size: usize,
+    },
+    /// A cut with fewer wires exists, so a cut of `size` isn't the minimum.
+    SmallerCut {
+        size: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::TooFewComponents => write!(f, "fewer than two components to split"),
+            Error::NoCut { size } => write!(f, "no cut of {} wires splits the components", size),
+            Error::SmallerCut { size, found } => write!(
+                f,
+                "cutting {} wires already splits the components, fewer than {}",
+                found, size
+            ),
+        }
+    }
+}
+
+/// The wires of a minimum cut and the two groups of components it leaves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut<'a> {
+    pub wires: Vec<(&'a str, &'a str)>,
+    pub groups: [Vec<&'a str>; 2],
+}
+
+/// Components connected by wires, numbered in order of appearance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wiring<'a> {
+    names: Vec<&'a str>,
+    wires: Vec<(usize, usize)>,
+    /// `(neighbour, wire)` for each wire at a component.
+    adjacency: Vec<Vec<(usize, usize)>>,
+}
+
+impl<'a> Wiring<'a> {
+    pub fn parse(input: &'a str) -> Self {
+        let mut indices = HashMap::new();
+        let mut wiring = Self {
+            names: Vec::new(),
+            wires: Vec::new(),
+            adjacency: Vec::new(),
+        };
+        let mut index = |wiring: &mut Self, name: &'a str| {
+            *indices.entry(name).or_insert_with(|| {
+                wiring.names.push(name);
+                wiring.adjacency.push(Vec::new());
+                wiring.names.len() - 1
+            })
+        };
+        for line in input.lines() {
+            let (component, others) = line.split_once(": ").unwrap();
+            let a = index(&mut wiring, component);
+            for other in others.split_ascii_whitespace() {
+                let b = index(&mut wiring, other);
+                let wire = wiring.wires.len();
+                wiring.wires.push((a, b));
+                wiring.adjacency[a].push((b, wire));
+                wiring.adjacency[b].push((a, wire));
+            }
+        }
+        wiring
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn wires(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.wires
+            .iter()
+            .map(|&(a, b)| (self.names[a], self.names[b]))
+    }
+
+    /// A cut with the fewest wires.
+    pub fn min_cut(&self) -> Result<Cut<'a>, Error> {
+        self.smallest_cut(usize::MAX)
+            .map(|(_, side)| self.cut_from(&side))
+    }
+
+    /// The cut of exactly `size` wires, which must also be a minimum cut.
+    pub fn cut(&self, size: usize) -> Result<Cut<'a>, Error> {
+        match self.smallest_cut(size + 1) {
+            Ok((found, side)) if found == size => Ok(self.cut_from(&side)),
+            Ok((found, _)) => Err(Error::SmallerCut { size, found }),
+            Err(Error::NoCut { .. }) => Err(Error::NoCut { size }),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Every cut separates the first component from some other one, so the
+    /// minimum cut is the smallest of the maximum flows from the first to each
+    /// other component. Each flow stops once it reaches the best cut so far,
+    /// or `limit`, and a component that gets that far is on the first
+    /// component's side of every smaller cut, so it joins the sources.
+    fn smallest_cut(&self, limit: usize) -> Result<(usize, Vec<bool>), Error> {
+        if self.len() < 2 {
+            return Err(Error::TooFewComponents);
+        }
+        let mut sources = vec![false; self.len()];
+        sources[0] = true;
+        let mut best: Option<(usize, Vec<bool>)> = None;
+        for sink in 1..self.len() {
+            let bound = best.as_ref().map_or(limit, |(size, _)| *size);
+            match self.max_flow(&sources, sink, bound) {
+                Some(cut) => best = Some(cut),
+                None => sources[sink] = true,
+            }
+        }
+        best.ok_or(Error::NoCut { size: limit - 1 })
+    }
+
+    /// The maximum flow from `sources` to `sink` with one unit through each
+    /// wire, and the components still reachable from `sources`, if the flow
+    /// is below `bound`.
+    fn max_flow(&self, sources: &[bool], sink: usize, bound: usize) -> Option<(usize, Vec<bool>)> {
+        // Flow through each wire from its first component to its second.
+        let mut flow = vec![0i8; self.wires.len()];
+        let mut flows = 0;
+        let mut through = vec![None; self.len()];
+        let mut queue = VecDeque::new();
+        loop {
+            let mut reached = sources.to_vec();
+            queue.clear();
+            queue.extend((0..self.len()).filter(|&node| sources[node]));
+            while let Some(node) = queue.pop_front() {
+                for &(next, wire) in &self.adjacency[node] {
+                    let forward = self.wires[wire].0 == node;
+                    let spare = if forward {
+                        flow[wire] < 1
+                    } else {
+                        flow[wire] > -1
+                    };
+                    if spare && !reached[next] {
+                        reached[next] = true;
+                        through[next] = Some((node, wire));
+                        queue.push_back(next);
+                    }
+                }
+                if reached[sink] {
+                    break;
+                }
+            }
+            if !reached[sink] {
+                return Some((flows, reached));
+            }
+
+            flows += 1;
+            if flows >= bound {
+                return None;
+            }
+            let mut node = sink;
+            while !sources[node] {
+                let (previous, wire) = through[node].unwrap();
+                flow[wire] += if self.wires[wire].0 == previous {
+                    1
+                } else {
+                    -1
+                };
+                node = previous;
+            }
+        }
+    }
+
+    fn cut_from(&self, side: &[bool]) -> Cut<'a> {
+        let wires = self
+            .wires
+            .iter()
+            .filter(|&&(a, b)| side[a] != side[b])
+            .map(|&(a, b)| (self.names[a], self.names[b]))
+            .collect();
+        let group = |inside: bool| {
+            self.names
+                .iter()
+                .zip(side)
+                .filter(|&(_, &side)| side == inside)
+                .map(|(&name, _)| name)
+                .collect()
+        };
+        Cut {
+            wires,
+            groups: [group(true), group(false)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let wiring = Wiring::parse(EXAMPLE);
+        assert_eq!(wiring.len(), 15);
+        assert_eq!(wiring.wires().count(), 33);
+
+        let mut cut = wiring.cut(3).unwrap();
+        for wire in &mut cut.wires {
+            if wire.0 > wire.1 {
+                *wire = (wire.1, wire.0);
+            }
+        }
+        cut.wires.sort();
+        assert_eq!(cut.wires, [("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+        let mut sizes = cut.groups.map(|group| group.len());
+        sizes.sort();
+        assert_eq!(sizes, [6, 9]);
+        assert_eq!(wiring.min_cut().unwrap().wires.len(), 3);
+    }
+
+    #[test]
+    fn errors() {
+        let wiring = Wiring::parse(EXAMPLE);
+        assert_eq!(wiring.cut(2), Err(Error::NoCut { size: 2 }));
+        assert_eq!(wiring.cut(4), Err(Error::SmallerCut { size: 4, found: 3 }));
+        assert_eq!(
+            Error::NoCut { size: 2 }.to_string(),
+            "no cut of 2 wires splits the components"
+        );
+
+        let single = Wiring::parse("a: a");
+        assert_eq!(single.min_cut(), Err(Error::TooFewComponents));
+    }
+
+    #[test]
+    fn any_size() {
+        // Two triangles joined by a single wire.
+        let wiring = Wiring::parse("a: b c\nb: c\nc: d\nd: e f\ne: f");
+        let cut = wiring.cut(1).unwrap();
+        assert_eq!(cut.wires, [("c", "d")]);
+        assert_eq!(cut.groups, [vec!["a", "b", "c"], vec!["d", "e", "f"]]);
+
+        // Already split in two.
+        let wiring = Wiring::parse("a: b\nc: d");
+        let cut = wiring.cut(0).unwrap();
+        assert!(cut.wires.is_empty());
+        assert_eq!(cut.groups, [vec!["a", "b"], vec!["c", "d"]]);
+    }
+}